@@ -0,0 +1,6 @@
+use gloo::worker::Registrable;
+use rewrapped::worker::DataWorker;
+
+fn main() {
+    DataWorker::registrar().register();
+}
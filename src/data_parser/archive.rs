@@ -0,0 +1,184 @@
+use std::io::{Cursor, Read};
+
+use zip::{result::ZipError, ZipArchive};
+
+use super::{error::ParseError, history_file::HistoryFile};
+
+/// Local file header signature every ZIP archive starts with.
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+pub(crate) const EXTENDED_AUDIO_PREFIX: &str = "Streaming_History_Audio_";
+pub(crate) const EXTENDED_VIDEO_PREFIX: &str = "Streaming_History_Video_";
+pub(crate) const ACCOUNT_DATA_PREFIX: &str = "StreamingHistory_music_";
+
+/// File name prefixes of the streaming history files inside a Spotify data export.
+const STREAMING_HISTORY_PREFIXES: [&str; 3] = [
+    EXTENDED_AUDIO_PREFIX,
+    EXTENDED_VIDEO_PREFIX,
+    ACCOUNT_DATA_PREFIX,
+];
+
+/// Returns `true` if `data` looks like a ZIP archive.
+pub fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(ZIP_MAGIC)
+}
+
+/// Returns `true` if the archive entry at `path` is a streaming history file, regardless of the
+/// folder it is stored in.
+pub fn is_streaming_history_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.ends_with(".json")
+        && STREAMING_HISTORY_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Reads every streaming history file out of a Spotify data export.
+///
+/// # Arguments
+///
+/// * `archive` - The uploaded ZIP archive (e.g. `my_spotify_data.zip`).
+///
+/// # Returns
+///
+/// Every matching entry, named after the archive and its file name inside it, e.g.
+/// `my_spotify_data.zip/Streaming_History_Audio_2023_1.json`, so that the files of two exports
+/// keep apart. Unrelated entries such as `ReadMe.pdf` are skipped.
+pub fn extract_streaming_histories(archive: &HistoryFile) -> Result<Vec<HistoryFile>, ParseError> {
+    read_streaming_histories(&archive.name, &archive.contents).map_err(|e| {
+        ParseError::InvalidArchive {
+            file: archive.name.clone(),
+            message: e.to_string(),
+        }
+    })
+}
+
+fn read_streaming_histories(
+    archive_name: &str,
+    archive: &[u8],
+) -> Result<Vec<HistoryFile>, ZipError> {
+    let mut archive = ZipArchive::new(Cursor::new(archive))?;
+    let mut files = vec![];
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if !entry.is_file() || !is_streaming_history_file(entry.name()) {
+            continue;
+        }
+        let name = format!(
+            "{}/{}",
+            archive_name,
+            entry.name().rsplit('/').next().unwrap_or_default()
+        );
+        let mut contents = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut contents)?;
+        files.push(HistoryFile::new(name, contents));
+    }
+    Ok(files)
+}
+
+/// Replaces every ZIP archive in `files` with the streaming history files it contains. Files
+/// that are not archives are passed through untouched.
+pub fn expand_archives(files: Vec<HistoryFile>) -> Result<Vec<HistoryFile>, ParseError> {
+    files.into_iter().try_fold(vec![], |mut expanded, file| {
+        if is_zip(&file.contents) {
+            expanded.extend(extract_streaming_histories(&file)?);
+        } else {
+            expanded.push(file);
+        }
+        Ok(expanded)
+    })
+}
+
+#[cfg(test)]
+mod test_archive {
+    use std::io::Write;
+
+    use super::*;
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    fn build_archive(entries: &[(&str, &[u8])]) -> HistoryFile {
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        for (name, contents) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        HistoryFile::new("my_spotify_data.zip", writer.finish().unwrap().into_inner())
+    }
+
+    #[test]
+    fn test_is_streaming_history_file() {
+        assert!(is_streaming_history_file(
+            "Spotify Extended Streaming History/Streaming_History_Audio_2023_1.json"
+        ));
+        assert!(is_streaming_history_file(
+            "Streaming_History_Video_2019-2023.json"
+        ));
+        assert!(is_streaming_history_file(
+            "Spotify Account Data/StreamingHistory_music_0.json"
+        ));
+        assert!(!is_streaming_history_file(
+            "Spotify Extended Streaming History/ReadMe.pdf"
+        ));
+        assert!(!is_streaming_history_file(
+            "__MACOSX/._Streaming_History_Audio_2023_1.json"
+        ));
+    }
+
+    #[test]
+    fn test_extract_streaming_histories_skips_unrelated_files() {
+        let archive = build_archive(&[
+            ("Spotify Extended Streaming History/ReadMe.pdf", b"%PDF"),
+            (
+                "Spotify Extended Streaming History/Streaming_History_Audio_2023_1.json",
+                b"[1]",
+            ),
+            (
+                "Spotify Extended Streaming History/Streaming_History_Video_2023.json",
+                b"[2]",
+            ),
+        ]);
+        let mut files = extract_streaming_histories(&archive).unwrap();
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            files,
+            vec![
+                HistoryFile::new(
+                    "my_spotify_data.zip/Streaming_History_Audio_2023_1.json",
+                    b"[1]".to_vec()
+                ),
+                HistoryFile::new(
+                    "my_spotify_data.zip/Streaming_History_Video_2023.json",
+                    b"[2]".to_vec()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_archives_passes_json_through() {
+        let archive = build_archive(&[("Streaming_History_Audio_2023_1.json", b"[1]")]);
+        let json = HistoryFile::new("Streaming_History_Audio_2022_0.json", b"[0]".to_vec());
+        let files = expand_archives(vec![json.clone(), archive]).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                json,
+                HistoryFile::new(
+                    "my_spotify_data.zip/Streaming_History_Audio_2023_1.json",
+                    b"[1]".to_vec()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_streaming_histories_invalid_archive() {
+        let archive = HistoryFile::new("my_spotify_data.zip", b"PK\x03\x04 truncated".to_vec());
+        assert!(matches!(
+            extract_streaming_histories(&archive),
+            Err(ParseError::InvalidArchive { file, .. }) if file == "my_spotify_data.zip"
+        ));
+    }
+}
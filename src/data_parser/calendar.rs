@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    metric::Metric,
+    period::Period,
+    playback_record::PlaybackRecord,
+    processing::group_by,
+    queries::{get_top_songs, get_total_listening_time_in_ms},
+    timezone::Timezone,
+    track::{Track, TrackGrouping},
+};
+
+/// The listening of a single day of the calendar.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub minutes: f32,
+    /// The track listened to the longest that day, `None` if no track was played.
+    pub top_track: Option<Track>,
+}
+
+/// A stream of the day picked in the calendar.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DayPlay {
+    /// When the stream ended, in local time.
+    pub ts: NaiveDateTime,
+    /// The track, or "Episode — Show" for podcasts.
+    pub name: String,
+    pub ms_played: u32,
+}
+
+/// Returns the listening of every day of `period` in `timezone`, in chronological order, including
+/// the days without any stream.
+///
+/// # Arguments
+///
+/// * `data` - The records of the period, in any order.
+/// * `period` - The period the calendar covers. [`Period::AllTime`] covers the days from the first
+///              to the last stream of `data`.
+/// * `timezone` - The timezone the days are computed in.
+pub fn get_calendar(
+    data: &Vec<PlaybackRecord>,
+    period: &Period,
+    timezone: Timezone,
+) -> Vec<CalendarDay> {
+    let days: HashMap<NaiveDate, Vec<PlaybackRecord>> =
+        group_by(data, |record| timezone.local(record).date_naive());
+    let Some((first, last)) = period.bounds().or_else(|| {
+        let first = days.keys().min()?;
+        let last = days.keys().max()?;
+        Some((*first, *last))
+    }) else {
+        return vec![];
+    };
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| match days.get(&date) {
+            Some(records) => CalendarDay {
+                date,
+                minutes: (get_total_listening_time_in_ms(records) as f64 / 60000.0).round() as f32,
+                top_track: get_top_songs(records, Metric::Time, TrackGrouping::Uri)
+                    .into_iter()
+                    .next()
+                    .map(|(track, _)| track),
+            },
+            None => CalendarDay {
+                date,
+                minutes: 0.0,
+                top_track: None,
+            },
+        })
+        .collect()
+}
+
+/// Returns what was played on `date` of `timezone`, in chronological order.
+pub fn get_day_plays(
+    data: &Vec<PlaybackRecord>,
+    date: NaiveDate,
+    timezone: Timezone,
+) -> Vec<DayPlay> {
+    let mut plays: Vec<DayPlay> = data
+        .iter()
+        .map(|record| (record, timezone.local(record).naive_local()))
+        .filter(|(_, ts)| ts.date() == date)
+        .map(|(record, ts)| DayPlay {
+            ts,
+            name: if !record.master_metadata_track_name.is_empty() {
+                Track::from(record).to_string()
+            } else if !record.podcast_episode_name.is_empty() {
+                format!(
+                    "{} — {}",
+                    record.podcast_episode_name, record.podcast_show_name
+                )
+            } else {
+                "Unknown".to_string()
+            },
+            ms_played: record.ms_played,
+        })
+        .collect();
+    plays.sort_by_key(|play| play.ts);
+    plays
+}
+
+#[cfg(test)]
+mod test_calendar {
+    use super::*;
+    use crate::data_parser::test_records::record;
+    use chrono::Timelike;
+
+    /// `minutes` of the track `title` that ended on the given day of October 2023 at `hour`.
+    fn play(title: &str, day: u32, hour: u32, minutes: u32) -> PlaybackRecord {
+        record()
+            .at(2023, 10, day, hour, 0)
+            .track(title)
+            .artist("Artist")
+            .minutes(minutes)
+            .build()
+    }
+
+    #[test]
+    fn test_get_calendar() {
+        let data = vec![play("a", 2, 9, 3), play("b", 2, 10, 5), play("a", 4, 9, 1)];
+        let calendar = get_calendar(&data, &Period::AllTime, Timezone::Utc);
+        assert_eq!(calendar.len(), 3);
+        assert_eq!(calendar[0].minutes, 8.0);
+        assert_eq!(
+            calendar[0]
+                .top_track
+                .as_ref()
+                .map(|track| track.title.as_str()),
+            Some("b")
+        );
+        assert_eq!(calendar[1].minutes, 0.0);
+        assert_eq!(calendar[1].top_track, None);
+
+        let year = get_calendar(&data, &Period::Year(2023), Timezone::Utc);
+        assert_eq!(year.len(), 365);
+        assert_eq!(
+            year[274].date,
+            NaiveDate::from_ymd_opt(2023, 10, 2).unwrap()
+        );
+        assert_eq!(year[274].minutes, 8.0);
+        assert!(get_calendar(&vec![], &Period::AllTime, Timezone::Utc).is_empty());
+        // 9:00 and 10:00 UTC are the day before in UTC-11.
+        let calendar = get_calendar(&data, &Period::AllTime, Timezone::Offset(-11 * 60));
+        assert_eq!(
+            calendar[0].date,
+            NaiveDate::from_ymd_opt(2023, 10, 1).unwrap()
+        );
+        assert_eq!(calendar[0].minutes, 8.0);
+    }
+
+    #[test]
+    fn test_get_day_plays() {
+        let data = vec![play("b", 2, 10, 5), play("a", 2, 9, 3), play("a", 4, 9, 1)];
+        let date = NaiveDate::from_ymd_opt(2023, 10, 2).unwrap();
+        let plays = get_day_plays(&data, date, Timezone::Utc);
+        assert_eq!(plays.len(), 2);
+        assert_eq!(plays[0].name, "a — Artist");
+        assert_eq!(plays[1].ms_played, 300_000);
+        assert_eq!(
+            get_day_plays(&data, date, Timezone::Offset(60))[0]
+                .ts
+                .hour(),
+            10
+        );
+    }
+}
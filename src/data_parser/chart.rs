@@ -0,0 +1,372 @@
+use std::str::FromStr;
+
+use chrono::{Month, Weekday};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    comparison::{compare_distributions, compare_rankings, Comparison},
+    content::ContentFilter,
+    discovery,
+    period::Period,
+    playback_record::PlaybackRecord,
+    queries::{self, QueryOptions},
+    report::with_thousands_separator,
+    sessions,
+    skips::{self, TrackLengths, MIN_PLAYS},
+};
+
+/// Number of entries compared between two periods in the top artists, songs and albums.
+const COMPARED_RANKS: usize = 10;
+/// Number of songs shown in the most skipped chart.
+const SKIPPED_SONGS: usize = 10;
+/// Number of artists shown in the top discoveries chart.
+const TOP_DISCOVERIES: usize = 10;
+
+/// The charts of the statistics view. Each one names the query it is computed with, see
+/// [`ChartType::query`].
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub enum ChartType {
+    Artists,
+    Songs,
+    Albums,
+    Week,
+    Months,
+    Days,
+    Day,
+    Platform,
+    Country,
+    Shows,
+    Episodes,
+    PodcastMonths,
+    Skips,
+    Sessions,
+    SessionStarts,
+    NewArtists,
+    Discoveries,
+    NewMusic,
+    Heatmap,
+}
+
+/// Formats a duration as e.g. "2 h 5 min" or "45 min".
+fn format_duration(duration: chrono::TimeDelta) -> String {
+    let minutes = duration.num_minutes();
+    if minutes < 60 {
+        format!("{} min", minutes)
+    } else {
+        format!("{} h {} min", minutes / 60, minutes % 60)
+    }
+}
+
+/// The most skipped songs of `dt`, with the share of their plays that were skipped.
+fn most_skipped(dt: &Vec<PlaybackRecord>, lengths: &TrackLengths) -> Vec<(String, f32)> {
+    skips::get_most_skipped_songs(dt, lengths, MIN_PLAYS)
+        .into_iter()
+        .take(SKIPPED_SONGS)
+        .map(|(track, rate)| (track.to_string(), (rate.rate() * 100.0).round()))
+        .collect()
+}
+
+impl ChartType {
+    /// Runs the query of this chart over the records `dt` of a period. `history` holds the records
+    /// of every period, which the discovery charts need to tell new music from known music.
+    pub fn query(
+        &self,
+        history: &Vec<PlaybackRecord>,
+        dt: &Vec<PlaybackRecord>,
+        options: &QueryOptions,
+    ) -> Vec<(String, f32)> {
+        match self {
+            Self::Albums => queries::get_top_albums_percentages(dt, 4.0, 5, options.metric),
+            Self::Artists => queries::get_top_artists_percentages(dt, 4.0, 5, options.metric),
+            Self::Songs => {
+                queries::get_top_songs_percentages(dt, 4.0, 5, options.metric, options.tracks)
+            }
+            Self::Week => queries::get_day_distribution(dt, options.timezone),
+            Self::Months => queries::get_months_distribution(dt, options.timezone),
+            Self::Platform => queries::get_top_platforms(dt),
+            Self::Country => queries::get_top_countries(dt),
+            Self::Days => queries::get_top_days(dt, 5, options.timezone),
+            Self::Day => queries::get_hours_of_the_day_distribution(dt, options.timezone),
+            Self::Shows => queries::get_top_shows_percentages(dt, 4.0, 5, options.metric),
+            Self::Episodes => queries::get_top_episodes_percentages(dt, 4.0, 5, options.metric),
+            Self::PodcastMonths => queries::get_podcast_hours_per_month(dt, options.timezone),
+            Self::Skips => most_skipped(dt, &TrackLengths::estimate(history)),
+            Self::Sessions => sessions::get_session_length_distribution(&sessions::get_sessions(
+                dt,
+                options.session_gap_minutes,
+            )),
+            Self::SessionStarts => sessions::get_session_starts_by_hour(
+                &sessions::get_sessions(dt, options.session_gap_minutes),
+                options.timezone,
+            ),
+            Self::NewArtists => discovery::get_new_artists_per_month(history, dt, options.timezone),
+            Self::Discoveries => discovery::get_top_discoveries(history, dt)
+                .into_iter()
+                .take(TOP_DISCOVERIES)
+                .map(|(artist, ms)| (artist, (ms as f64 / 60000.0).round() as f32))
+                .collect(),
+            Self::NewMusic => discovery::get_new_music_share(history, dt),
+            Self::Heatmap => {
+                queries::get_weekday_hour_distribution(dt, options.normalize, options.timezone)
+            }
+        }
+    }
+    /// Returns the key figures shown below the chart, see `ChartResult::summary`.
+    pub fn summarize(
+        &self,
+        dt: &Vec<PlaybackRecord>,
+        options: &QueryOptions,
+    ) -> Vec<(String, String)> {
+        match self {
+            Self::Sessions | Self::SessionStarts => {
+                let all_sessions = sessions::get_sessions(dt, options.session_gap_minutes);
+                let mut summary = vec![
+                    (
+                        "Sessions".to_string(),
+                        with_thousands_separator(all_sessions.len()),
+                    ),
+                    (
+                        "Tracks per session".to_string(),
+                        format!(
+                            "{:.1}",
+                            sessions::get_average_tracks_per_session(&all_sessions)
+                        ),
+                    ),
+                ];
+                if let Some(longest) = sessions::get_longest_session(&all_sessions) {
+                    let start = options.timezone.at(longest.start, &longest.country);
+                    summary.push((
+                        "Longest session".to_string(),
+                        format!(
+                            "{} on {} ({} tracks)",
+                            format_duration(longest.duration()),
+                            start.format("%d/%m/%y"),
+                            longest.tracks
+                        ),
+                    ));
+                }
+                summary
+            }
+            _ => vec![],
+        }
+    }
+    /// Compares the data of two periods. Returns `None` for charts that can only be shown side by
+    /// side.
+    pub fn compare(
+        &self,
+        history: &Vec<PlaybackRecord>,
+        dt: &Vec<PlaybackRecord>,
+        previous: &Vec<PlaybackRecord>,
+        options: &QueryOptions,
+    ) -> Option<Comparison> {
+        let ranks = |query: &dyn Fn(&Vec<PlaybackRecord>) -> Vec<(String, u64)>| {
+            Comparison::Ranks(compare_rankings(
+                &query(dt),
+                &query(previous),
+                COMPARED_RANKS,
+            ))
+        };
+        let values = |query: &dyn Fn(&Vec<PlaybackRecord>) -> Vec<(String, f32)>| {
+            Comparison::Values(compare_distributions(
+                &query(dt),
+                &query(previous),
+                |name| self.category_order(name),
+            ))
+        };
+        match self {
+            Self::Artists => Some(ranks(&|data| {
+                queries::get_top_artists(data, options.metric)
+            })),
+            Self::Songs => {
+                let [current, previous] = queries::name_songs([
+                    queries::get_top_songs(dt, options.metric, options.tracks),
+                    queries::get_top_songs(previous, options.metric, options.tracks),
+                ]);
+                Some(Comparison::Ranks(compare_rankings(
+                    &current,
+                    &previous,
+                    COMPARED_RANKS,
+                )))
+            }
+            Self::Albums => Some(ranks(&|data| {
+                queries::get_top_album_names(data, options.metric)
+            })),
+            Self::Week => Some(values(&|data| {
+                queries::get_day_distribution(data, options.timezone)
+            })),
+            Self::Months => Some(values(&|data| {
+                queries::get_months_distribution(data, options.timezone)
+            })),
+            Self::Day => Some(values(&|data| {
+                queries::get_hours_of_the_day_minutes(data, options.timezone)
+            })),
+            Self::Platform => Some(values(&queries::get_top_platforms)),
+            Self::Country => Some(values(&queries::get_top_countries)),
+            Self::Shows => Some(ranks(&|data| queries::get_top_shows(data, options.metric))),
+            Self::Episodes => Some(ranks(&|data| {
+                queries::get_top_episodes(data, options.metric)
+            })),
+            Self::Skips => {
+                let lengths = TrackLengths::estimate(history);
+                Some(Comparison::Ranks(compare_rankings(
+                    &most_skipped(dt, &lengths),
+                    &most_skipped(previous, &lengths),
+                    COMPARED_RANKS,
+                )))
+            }
+            Self::Sessions | Self::SessionStarts | Self::NewMusic => {
+                Some(Comparison::Values(compare_distributions(
+                    &self.query(history, dt, options),
+                    &self.query(history, previous, options),
+                    |name| self.category_order(name),
+                )))
+            }
+            Self::Days
+            | Self::PodcastMonths
+            | Self::NewArtists
+            | Self::Discoveries
+            | Self::Heatmap => None,
+        }
+    }
+    /// Returns the position of a category of this chart in its natural order, e.g. the number
+    /// of a month. `None` for charts whose categories are ranked by value instead.
+    fn category_order(&self, name: &str) -> Option<u32> {
+        match self {
+            Self::Week => Weekday::from_str(name)
+                .ok()
+                .map(|weekday| weekday.number_from_monday()),
+            Self::Months => Month::from_str(name)
+                .ok()
+                .map(|month| month.number_from_month()),
+            Self::Day | Self::SessionStarts => name.parse().ok(),
+            _ => None,
+        }
+    }
+    /// Returns `false` if this chart means nothing for `period`. The discovery charts compare a
+    /// period with the listening before it, and nothing comes before all time.
+    pub fn supports_period(&self, period: &Period) -> bool {
+        !matches!(
+            (self, period),
+            (
+                Self::NewArtists | Self::Discoveries | Self::NewMusic,
+                Period::AllTime
+            )
+        )
+    }
+    /// Returns `true` if this chart relies on fields that only the extended streaming history
+    /// contains. The account data export has no album names.
+    pub fn needs_extended_fields(&self) -> bool {
+        matches!(
+            self,
+            Self::Albums | Self::Platform | Self::Country | Self::Skips
+        )
+    }
+    /// Returns `true` if this chart ranks its entries and can measure them with any [`Metric`].
+    pub fn supports_metric(&self) -> bool {
+        matches!(
+            self,
+            Self::Artists | Self::Songs | Self::Albums | Self::Shows | Self::Episodes
+        )
+    }
+    /// Returns `true` if this chart splits the streams into sessions.
+    pub fn uses_sessions(&self) -> bool {
+        matches!(self, Self::Sessions | Self::SessionStarts)
+    }
+    /// Returns the content this chart is always computed for, regardless of the selected
+    /// [`ContentFilter`].
+    pub fn content(&self) -> Option<ContentFilter> {
+        match self {
+            Self::Shows | Self::Episodes | Self::PodcastMonths => Some(ContentFilter::Podcasts),
+            _ => None,
+        }
+    }
+}
+impl std::str::FromStr for ChartType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "artist" | "artists" => Ok(Self::Artists),
+            "song" | "songs" => Ok(Self::Songs),
+            "album" | "albums" => Ok(Self::Albums),
+            "week" | "weeks" => Ok(Self::Week),
+            "months" | "month" => Ok(Self::Months),
+            "day" => Ok(Self::Day),
+            "days" => Ok(Self::Days),
+            "platform" | "platforms" => Ok(Self::Platform),
+            "country" | "countries" => Ok(Self::Country),
+            "show" | "shows" | "podcasts" => Ok(Self::Shows),
+            "episode" | "episodes" => Ok(Self::Episodes),
+            "podcastmonths" | "podcast-months" => Ok(Self::PodcastMonths),
+            "skips" | "skipped" | "most-skipped" => Ok(Self::Skips),
+            "session" | "sessions" => Ok(Self::Sessions),
+            "sessionstarts" | "session-starts" => Ok(Self::SessionStarts),
+            "newartists" | "new-artists" => Ok(Self::NewArtists),
+            "discoveries" => Ok(Self::Discoveries),
+            "newmusic" | "new-music" => Ok(Self::NewMusic),
+            "heatmap" => Ok(Self::Heatmap),
+            _ => Err(()),
+        }
+    }
+}
+impl std::fmt::Display for ChartType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Artists => "Artists".to_string(),
+                Self::Songs => "Songs".to_string(),
+                Self::Albums => "Albums".to_string(),
+                Self::Week => "Week".to_string(),
+                Self::Months => "Months".to_string(),
+                Self::Days => "Days".to_string(),
+                Self::Platform => "Platform".to_string(),
+                Self::Country => "Country".to_string(),
+                Self::Day => "Day".to_string(),
+                Self::Shows => "Shows".to_string(),
+                Self::Episodes => "Episodes".to_string(),
+                Self::PodcastMonths => "PodcastMonths".to_string(),
+                Self::Skips => "Skips".to_string(),
+                Self::Sessions => "Sessions".to_string(),
+                Self::SessionStarts => "SessionStarts".to_string(),
+                Self::NewArtists => "NewArtists".to_string(),
+                Self::Discoveries => "Discoveries".to_string(),
+                Self::NewMusic => "NewMusic".to_string(),
+                Self::Heatmap => "Heatmap".to_string(),
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_chart {
+    use super::*;
+    use crate::data_parser::test_records::record;
+
+    #[test]
+    fn test_compare_months_in_calendar_order() {
+        let current = vec![
+            record().at(2023, 1, 10, 12, 0).build(),
+            record().at(2023, 3, 10, 12, 0).build(),
+        ];
+        let previous = vec![
+            record().at(2022, 2, 10, 12, 0).build(),
+            record().at(2022, 12, 10, 12, 0).build(),
+        ];
+        let Some(Comparison::Values(changes)) =
+            ChartType::Months.compare(&current, &current, &previous, &QueryOptions::default())
+        else {
+            panic!("months are compared value by value");
+        };
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["January", "February", "March", "December"]);
+    }
+
+    #[test]
+    fn test_discoveries_need_a_part_of_the_history() {
+        assert!(!ChartType::NewArtists.supports_period(&Period::AllTime));
+        assert!(ChartType::NewArtists.supports_period(&Period::Year(2023)));
+        assert!(ChartType::Artists.supports_period(&Period::AllTime));
+    }
+}
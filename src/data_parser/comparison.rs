@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The rank of an entry (artist, song, album, ...) in two periods.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RankChange {
+    pub name: String,
+    /// The one-based rank in the current period.
+    pub rank: usize,
+    /// The one-based rank in the previous period, `None` if it was not listened to back then.
+    pub previous_rank: Option<usize>,
+}
+
+impl RankChange {
+    /// Returns how many places the entry climbed (positive) or fell (negative), `None` for new
+    /// entries.
+    pub fn places_climbed(&self) -> Option<i64> {
+        self.previous_rank
+            .map(|previous_rank| previous_rank as i64 - self.rank as i64)
+    }
+}
+
+/// The result of running the same query over two periods.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Comparison {
+    /// Rank changes of a top list (artists, songs, albums).
+    Ranks(Vec<RankChange>),
+    /// Percentage changes of a distribution (months, weekdays, hours, ...).
+    Values(Vec<ValueChange>),
+}
+
+/// The value of a category (month, weekday, hour, ...) in two periods.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValueChange {
+    pub name: String,
+    pub value: f32,
+    pub previous_value: f32,
+}
+
+impl ValueChange {
+    /// Returns the change from the previous to the current period in percent, rounded to one
+    /// decimal. `None` if nothing was listened to in the previous period.
+    pub fn percentage_change(&self) -> Option<f32> {
+        if self.previous_value == 0.0 {
+            return None;
+        }
+        let change = (self.value - self.previous_value) / self.previous_value * 100.0;
+        Some((change * 10.0).round() / 10.0)
+    }
+}
+
+/// Compares the top `n` entries of two rankings.
+///
+/// # Arguments
+///
+/// * `current` - A ranking of the current period, as returned by e.g. `get_top_artists`.
+/// * `previous` - The same ranking for the period to compare with.
+/// * `n` - The number of entries of `current` to compare.
+///
+/// # Returns
+///
+/// A vector with a [`RankChange`] for each of the top `n` entries of `current`, in order.
+pub fn compare_rankings<V>(
+    current: &[(String, V)],
+    previous: &[(String, V)],
+    n: usize,
+) -> Vec<RankChange> {
+    let previous_ranks: HashMap<&str, usize> = previous
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| (name.as_str(), i + 1))
+        .collect();
+    current
+        .iter()
+        .take(n)
+        .enumerate()
+        .map(|(i, (name, _))| RankChange {
+            name: name.clone(),
+            rank: i + 1,
+            previous_rank: previous_ranks.get(name.as_str()).copied(),
+        })
+        .collect()
+}
+
+/// Compares two distributions category by category.
+///
+/// # Arguments
+///
+/// * `current` - A distribution of the current period, as returned by e.g.
+///               `get_months_distribution`.
+/// * `previous` - The same distribution for the period to compare with.
+/// * `order` - The natural order of the categories, e.g. the number of a month. Distributions
+///             without one, such as rankings, can return the same key for every category.
+///
+/// # Returns
+///
+/// A vector with a [`ValueChange`] for every category of either distribution, sorted by
+/// `order`. Categories of the same order keep the order of `current`, and the ones only present
+/// in `previous` come after them.
+pub fn compare_distributions<K, F>(
+    current: &[(String, f32)],
+    previous: &[(String, f32)],
+    order: F,
+) -> Vec<ValueChange>
+where
+    K: Ord,
+    F: Fn(&str) -> K,
+{
+    let previous_values: HashMap<&str, f32> = previous
+        .iter()
+        .map(|(name, value)| (name.as_str(), *value))
+        .collect();
+    let current_values: HashMap<&str, f32> = current
+        .iter()
+        .map(|(name, value)| (name.as_str(), *value))
+        .collect();
+    let mut changes: Vec<ValueChange> = current
+        .iter()
+        .map(|(name, value)| ValueChange {
+            name: name.clone(),
+            value: *value,
+            previous_value: previous_values.get(name.as_str()).copied().unwrap_or(0.0),
+        })
+        .chain(
+            previous
+                .iter()
+                .filter(|(name, _)| !current_values.contains_key(name.as_str()))
+                .map(|(name, value)| ValueChange {
+                    name: name.clone(),
+                    value: 0.0,
+                    previous_value: *value,
+                }),
+        )
+        .collect();
+    changes.sort_by_key(|change| order(&change.name));
+    changes
+}
+
+#[cfg(test)]
+mod test_comparison {
+    use super::*;
+
+    fn ranking(names: &[&str]) -> Vec<(String, u64)> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), (names.len() - i) as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_compare_rankings() {
+        let current = ranking(&["a", "b", "c", "d"]);
+        let previous = ranking(&["b", "c", "a"]);
+        let changes = compare_rankings(&current, &previous, 3);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].places_climbed(), Some(2));
+        assert_eq!(changes[1].places_climbed(), Some(-1));
+        assert_eq!(changes[2].places_climbed(), Some(-1));
+        let changes = compare_rankings(&current, &previous, 10);
+        assert_eq!(changes[3].previous_rank, None);
+        assert_eq!(changes[3].places_climbed(), None);
+    }
+
+    #[test]
+    fn test_compare_distributions() {
+        let current = vec![("January".to_string(), 150.0), ("March".to_string(), 10.0)];
+        let previous = vec![
+            ("January".to_string(), 100.0),
+            ("February".to_string(), 40.0),
+        ];
+        let changes = compare_distributions(&current, &previous, |_| ());
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["January", "March", "February"]);
+        assert_eq!(changes[0].percentage_change(), Some(50.0));
+        assert_eq!(changes[1].percentage_change(), None);
+        assert_eq!(changes[2].percentage_change(), Some(-100.0));
+    }
+
+    #[test]
+    fn test_categories_only_in_the_previous_period_keep_their_place() {
+        let hours = |hours: &[u32]| -> Vec<(String, f32)> {
+            hours.iter().map(|hour| (hour.to_string(), 1.0)).collect()
+        };
+        let changes = compare_distributions(&hours(&[1, 3, 24]), &hours(&[2, 3, 23]), |name| {
+            name.parse::<u32>().ok()
+        });
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["1", "2", "3", "23", "24"]);
+        assert_eq!(changes[1].value, 0.0);
+        assert_eq!(changes[1].previous_value, 1.0);
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::playback_record::ContentType;
+
+/// The kinds of content the statistics are computed for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ContentFilter {
+    /// Every stream.
+    #[default]
+    All,
+    /// Songs, and the streams of unknown type, see [`ContentType::is_music`].
+    Music,
+    Podcasts,
+    Audiobooks,
+}
+
+impl ContentFilter {
+    pub const ALL: [Self; 4] = [Self::All, Self::Music, Self::Podcasts, Self::Audiobooks];
+
+    /// Returns `true` if streams of `content_type` pass this filter.
+    pub fn contains(&self, content_type: ContentType) -> bool {
+        match self {
+            Self::All => true,
+            Self::Music => content_type.is_music(),
+            Self::Podcasts => content_type == ContentType::Episode,
+            Self::Audiobooks => content_type == ContentType::Audiobook,
+        }
+    }
+}
+
+impl fmt::Display for ContentFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "Everything"),
+            Self::Music => write!(f, "Music"),
+            Self::Podcasts => write!(f, "Podcasts"),
+            Self::Audiobooks => write!(f, "Audiobooks"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_content {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        assert!(ContentFilter::All.contains(ContentType::Episode));
+        assert!(ContentFilter::Music.contains(ContentType::Track));
+        assert!(ContentFilter::Music.contains(ContentType::Unknown));
+        assert!(!ContentFilter::Music.contains(ContentType::Episode));
+        assert!(!ContentFilter::Music.contains(ContentType::Audiobook));
+        assert!(ContentFilter::Podcasts.contains(ContentType::Episode));
+        assert!(!ContentFilter::Podcasts.contains(ContentType::Unknown));
+        assert!(ContentFilter::Audiobooks.contains(ContentType::Audiobook));
+    }
+}
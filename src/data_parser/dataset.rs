@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    playback_record::PlaybackRecord, processing::append_records, queries, timezone::Timezone,
+};
+
+/// A change to the dataset, sent to the data worker that owns it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum DataAction {
+    /// Adds records to the dataset. The ones it already contains are not added again, they get
+    /// the sources of their new copy instead.
+    Append(Vec<PlaybackRecord>),
+    Clear,
+    /// Removes the given file from the sources of every record, and the records it was the only
+    /// source of. An empty name removes the records whose source is not known.
+    RemoveSource(String),
+}
+
+impl DataAction {
+    /// Applies the change to `records`.
+    pub fn apply(self, records: &mut Vec<PlaybackRecord>) {
+        match self {
+            Self::Append(new) => {
+                append_records(records, new);
+            }
+            Self::Clear => records.clear(),
+            Self::RemoveSource(source) => records.retain_mut(|record| {
+                if record.sources.is_empty() {
+                    return !source.is_empty();
+                }
+                record.sources.retain(|s| *s != source);
+                !record.sources.is_empty()
+            }),
+        }
+    }
+}
+
+/// What the views need to know about the dataset. The data worker computes it once per change of
+/// the dataset, so that the UI thread never goes over the records.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DatasetSummary {
+    /// Number of changes made to the dataset. The first change is the dataset restored from the
+    /// browser storage.
+    pub revision: usize,
+    pub records: usize,
+    /// The files the records were imported from, see [`queries::get_sources`].
+    pub sources: Vec<(String, usize)>,
+    /// The years with at least one stream in the timezone the summary was made for, most recent
+    /// first.
+    pub years: Vec<i32>,
+    /// Number of records of the extended streaming history, the only ones the charts that
+    /// [need extended fields](super::chart::ChartType::needs_extended_fields) are computed over.
+    pub extended_records: usize,
+    /// See [`queries::has_podcasts`].
+    pub has_podcasts: bool,
+}
+
+impl DatasetSummary {
+    pub fn new(records: &Vec<PlaybackRecord>, revision: usize, timezone: Timezone) -> Self {
+        DatasetSummary {
+            revision,
+            records: records.len(),
+            sources: queries::get_sources(records),
+            years: queries::get_years(records, timezone),
+            extended_records: records
+                .iter()
+                .filter(|record| record.has_extended_fields())
+                .count(),
+            has_podcasts: queries::has_podcasts(records),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+
+    /// Returns `true` if any record comes from the extended streaming history.
+    pub fn has_extended_fields(&self) -> bool {
+        self.extended_records > 0
+    }
+
+    /// Returns `true` if the records come from both the extended streaming history and the
+    /// account data export.
+    pub fn has_mixed_schemas(&self) -> bool {
+        self.has_extended_fields() && self.extended_records < self.records
+    }
+}
+
+#[cfg(test)]
+mod test_dataset {
+    use super::*;
+    use crate::data_parser::{
+        history_file::HistoryFile, playback_record::HistorySchema, test_records::record,
+    };
+    const json: &str = include_str!("./test/test_data.json");
+
+    #[test]
+    fn test_remove_overlapping_source() {
+        // The first export covers every record, the second one only the first half of them.
+        let all: Vec<serde_json::Value> = serde_json::from_str(json).unwrap();
+        let half = serde_json::to_vec(&all[..all.len() / 2]).unwrap();
+        let files = vec![
+            HistoryFile::new("first.zip/Streaming_History_Audio_2023_0.json", json.into()),
+            HistoryFile::new("second.zip/Streaming_History_Audio_2023_0.json", half),
+        ];
+        let mut records = vec![];
+        for file in files {
+            let new = PlaybackRecord::from_files(&[file]).unwrap();
+            DataAction::Append(new).apply(&mut records);
+        }
+        let total = records.len();
+        assert_eq!(total, all.len());
+
+        let mut without_first = records.clone();
+        DataAction::RemoveSource("first.zip/Streaming_History_Audio_2023_0.json".to_string())
+            .apply(&mut without_first);
+        assert_eq!(without_first.len(), all.len() / 2);
+        assert!(without_first
+            .iter()
+            .all(|record| record.sources == ["second.zip/Streaming_History_Audio_2023_0.json"]));
+
+        DataAction::RemoveSource("second.zip/Streaming_History_Audio_2023_0.json".to_string())
+            .apply(&mut records);
+        assert_eq!(records.len(), total);
+        DataAction::RemoveSource("first.zip/Streaming_History_Audio_2023_0.json".to_string())
+            .apply(&mut records);
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_summary() {
+        let records = vec![
+            record().at(2022, 12, 31, 23, 30).build(),
+            record().at(2023, 6, 1, 12, 0).build(),
+        ];
+        let summary = DatasetSummary::new(&records, 3, Timezone::Offset(60));
+        assert_eq!(summary.revision, 3);
+        assert_eq!(summary.records, 2);
+        assert_eq!(summary.years, vec![2023]);
+        assert_eq!(summary.sources, queries::get_sources(&records));
+        assert!(summary.has_extended_fields());
+        assert!(!summary.has_mixed_schemas());
+        assert!(!summary.has_podcasts);
+        assert!(DatasetSummary::default().is_empty());
+
+        let mut mixed = records.clone();
+        mixed[0].schema = HistorySchema::AccountData;
+        let summary = DatasetSummary::new(&mixed, 4, Timezone::Utc);
+        assert_eq!(summary.extended_records, 1);
+        assert!(summary.has_mixed_schemas());
+        assert_eq!(
+            queries::get_records_with_extended_fields(&mixed),
+            vec![mixed[1].clone()]
+        );
+    }
+}
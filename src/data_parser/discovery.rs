@@ -0,0 +1,236 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+use super::{
+    playback_record::PlaybackRecord,
+    queries::get_total_listening_time_in_ms,
+    timezone::Timezone,
+    track::{Album, TrackGrouping, TrackKey},
+};
+
+/// The first-ever stream of every track, artist and album of a listening history.
+pub struct FirstListens {
+    pub tracks: HashMap<TrackKey, DateTime<Utc>>,
+    pub artists: HashMap<String, DateTime<Utc>>,
+    pub albums: HashMap<Album, DateTime<Utc>>,
+}
+
+fn keep_earliest<K: Eq + Hash>(map: &mut HashMap<K, DateTime<Utc>>, key: K, ts: DateTime<Utc>) {
+    let first = map.entry(key).or_insert(ts);
+    *first = ts.min(*first);
+}
+
+impl FirstListens {
+    /// Finds the first streams of `history`, which should be the whole listening history so that
+    /// music heard before a period is not mistaken for a discovery of that period.
+    pub fn new(history: &Vec<PlaybackRecord>) -> Self {
+        let mut first_listens = FirstListens {
+            tracks: HashMap::new(),
+            artists: HashMap::new(),
+            albums: HashMap::new(),
+        };
+        for record in history {
+            if let Some(key) = TrackGrouping::Uri.key(record) {
+                keep_earliest(&mut first_listens.tracks, key, record.ts);
+            }
+            if !record.master_metadata_album_artist_name.is_empty() {
+                keep_earliest(
+                    &mut first_listens.artists,
+                    record.master_metadata_album_artist_name.clone(),
+                    record.ts,
+                );
+            }
+            if let Some(album) = Album::of(record) {
+                keep_earliest(&mut first_listens.albums, album, record.ts);
+            }
+        }
+        first_listens
+    }
+}
+
+/// Returns the artists whose first stream of `history` is one of `data`, i.e. the artists first
+/// heard during the period of `data`, with the time of that stream.
+///
+/// Over the whole history every artist is new, so this is only meaningful for a part of it.
+pub fn get_new_artists(
+    history: &Vec<PlaybackRecord>,
+    data: &Vec<PlaybackRecord>,
+) -> Vec<(String, DateTime<Utc>)> {
+    let first_listens = FirstListens::new(history);
+    data.iter()
+        .filter(|record| {
+            first_listens
+                .artists
+                .get(&record.master_metadata_album_artist_name)
+                == Some(&record.ts)
+        })
+        .map(|record| (record.master_metadata_album_artist_name.clone(), record.ts))
+        .collect::<HashMap<String, DateTime<Utc>>>()
+        .into_iter()
+        .collect()
+}
+
+/// Returns the number of artists discovered in every month of the period of `data`, in
+/// chronological order, e.g. `("Oct 2023", 12.0)`. Months are those of `timezone`, and months
+/// without discoveries are left out.
+pub fn get_new_artists_per_month(
+    history: &Vec<PlaybackRecord>,
+    data: &Vec<PlaybackRecord>,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
+    let new_artists: HashMap<String, DateTime<Utc>> =
+        get_new_artists(history, data).into_iter().collect();
+    let mut months: HashMap<(i32, u32), usize> = HashMap::new();
+    // The first stream of every new artist lies in `data`, and tells where it was played from
+    for record in data {
+        if new_artists.get(&record.master_metadata_album_artist_name) == Some(&record.ts) {
+            let local = timezone.local(record);
+            *months.entry((local.year(), local.month())).or_insert(0) += 1;
+        }
+    }
+    let mut months: Vec<((i32, u32), usize)> = months.into_iter().collect();
+    months.sort_by_key(|(month, _)| *month);
+    months
+        .into_iter()
+        .map(|((year, month), count)| {
+            let label = NaiveDate::from_ymd_opt(year, month, 1)
+                .map(|date| date.format("%b %Y").to_string())
+                .unwrap_or_default();
+            (label, count as f32)
+        })
+        .collect()
+}
+
+/// Returns the artists discovered during the period of `data`, ranked by the time spent
+/// listening to them during that period (in ms).
+pub fn get_top_discoveries(
+    history: &Vec<PlaybackRecord>,
+    data: &Vec<PlaybackRecord>,
+) -> Vec<(String, u64)> {
+    let new_artists: HashMap<String, DateTime<Utc>> =
+        get_new_artists(history, data).into_iter().collect();
+    let mut time: HashMap<&str, u64> = HashMap::new();
+    for record in data {
+        if new_artists.contains_key(&record.master_metadata_album_artist_name) {
+            *time
+                .entry(&record.master_metadata_album_artist_name)
+                .or_insert(0) += record.ms_played as u64;
+        }
+    }
+    let mut discoveries: Vec<(String, u64)> = time
+        .into_iter()
+        .map(|(artist, ms)| (artist.to_string(), ms))
+        .collect();
+    discoveries.sort_by(|(a1, d1), (a2, d2)| d2.cmp(d1).then(a1.cmp(a2)));
+    discoveries
+}
+
+/// Returns the share of the listening time of `data` (in percent) that went to tracks whose first
+/// stream of `history` is one of `data`, and to tracks known before, as
+/// `[("New", _), ("Known", _)]`.
+pub fn get_new_music_share(
+    history: &Vec<PlaybackRecord>,
+    data: &Vec<PlaybackRecord>,
+) -> Vec<(String, f32)> {
+    let total = get_total_listening_time_in_ms(data);
+    if total == 0 {
+        return vec![];
+    }
+    let first_listens = FirstListens::new(history);
+    let new_tracks: HashSet<TrackKey> = data
+        .iter()
+        .filter_map(|record| {
+            let key = TrackGrouping::Uri.key(record)?;
+            (first_listens.tracks.get(&key) == Some(&record.ts)).then_some(key)
+        })
+        .collect();
+    let new: u64 = data
+        .iter()
+        .filter(|record| {
+            TrackGrouping::Uri
+                .key(record)
+                .is_some_and(|key| new_tracks.contains(&key))
+        })
+        .map(|record| record.ms_played as u64)
+        .sum();
+    let share = |ms: u64| ((ms as f64 / total as f64 * 1000.0).round() / 10.0) as f32;
+    vec![
+        ("New".to_string(), share(new)),
+        ("Known".to_string(), share(total - new)),
+    ]
+}
+
+#[cfg(test)]
+mod test_discovery {
+    use super::*;
+    use crate::data_parser::test_records::record;
+    use chrono::TimeZone;
+
+    /// One minute of the track `title` by `artist` on the given day of 2023.
+    fn play(artist: &str, title: &str, month: u32, day: u32) -> PlaybackRecord {
+        record()
+            .at(2023, month, day, 12, 0)
+            .artist(artist)
+            .track(title)
+            .build()
+    }
+
+    fn history() -> Vec<PlaybackRecord> {
+        vec![
+            play("Old", "a", 1, 5),
+            play("Old", "b", 2, 5),
+            play("New", "c", 2, 10),
+            play("New", "c", 2, 11),
+            play("Newer", "d", 3, 1),
+            play("Old", "a", 3, 2),
+        ]
+    }
+
+    #[test]
+    fn test_first_listens() {
+        let first_listens = FirstListens::new(&history());
+        assert_eq!(
+            first_listens.artists["Old"],
+            Utc.with_ymd_and_hms(2023, 1, 5, 12, 0, 0).unwrap()
+        );
+        assert_eq!(first_listens.artists.len(), 3);
+        assert_eq!(first_listens.tracks.len(), 4);
+    }
+
+    #[test]
+    fn test_discoveries() {
+        let history = history();
+        // February and March.
+        let period = history[1..].to_vec();
+        assert_eq!(
+            get_new_artists_per_month(&history, &period, Timezone::Utc),
+            vec![("Feb 2023".to_string(), 1.0), ("Mar 2023".to_string(), 1.0)]
+        );
+        assert_eq!(
+            get_top_discoveries(&history, &period),
+            vec![("New".to_string(), 120_000), ("Newer".to_string(), 60_000)]
+        );
+        // "b" is new, "a" was heard in January.
+        assert_eq!(
+            get_new_music_share(&history, &period),
+            vec![("New".to_string(), 80.0), ("Known".to_string(), 20.0)]
+        );
+        assert!(get_new_music_share(&history, &vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_artists_heard_before_the_period_are_not_new() {
+        let history = history();
+        // Only the second stream of "New", its first one was the day before.
+        let period = history[3..4].to_vec();
+        assert!(get_new_artists(&history, &period).is_empty());
+        assert_eq!(
+            get_new_music_share(&history, &period),
+            vec![("New".to_string(), 0.0), ("Known".to_string(), 100.0)]
+        );
+    }
+}
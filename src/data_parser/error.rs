@@ -0,0 +1,191 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_path_to_error::{Path, Segment};
+
+use super::report::with_thousands_separator;
+
+/// An error that occurred while reading the uploaded streaming history.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// An uploaded archive could not be opened, or one of its entries could not be read.
+    InvalidArchive { file: String, message: String },
+    /// A file is not a JSON array of records, e.g. it is not JSON at all or has trailing data.
+    InvalidFile { file: String, message: String },
+    /// A single record of a file could not be read.
+    ///
+    /// `index` is zero based and `field` is the name of the offending field, if it is known.
+    InvalidRecord {
+        file: String,
+        index: usize,
+        field: Option<String>,
+        message: String,
+    },
+}
+
+impl ParseError {
+    /// Builds an error from a `serde_json::Error` and the path at which it occurred, as recorded
+    /// by `serde_path_to_error`. The file name is left empty, see [`ParseError::in_file`].
+    pub(crate) fn from_serde(error: &serde_json::Error, path: &Path) -> Self {
+        let message = serde_message(error);
+        let mut segments = path.iter();
+        match segments.next() {
+            Some(Segment::Seq { index }) => Self::invalid_record(*index, message, segments.next()),
+            _ => Self::InvalidFile {
+                file: String::new(),
+                message,
+            },
+        }
+    }
+
+    /// Like [`ParseError::from_serde`], for an error that occurred while deserializing the single
+    /// record at `index`. `path` is relative to that record.
+    pub(crate) fn from_record_serde(index: usize, error: &serde_json::Error, path: &Path) -> Self {
+        Self::invalid_record(index, serde_message(error), path.iter().next())
+    }
+
+    fn invalid_record(index: usize, message: String, field: Option<&Segment>) -> Self {
+        let field = match field {
+            Some(Segment::Map { key }) => Some(key.clone()),
+            _ => message
+                .strip_prefix("missing field `")
+                .and_then(|rest| rest.split('`').next())
+                .map(|field| field.to_string()),
+        };
+        Self::InvalidRecord {
+            file: String::new(),
+            index,
+            field,
+            message,
+        }
+    }
+
+    /// Describes what went wrong, without saying where. Rejected records with the same reason are
+    /// counted together in the [`ImportReport`](super::report::ImportReport).
+    pub fn reason(&self) -> String {
+        match self {
+            Self::InvalidArchive { message, .. } => {
+                format!("the archive could not be read ({})", message)
+            }
+            Self::InvalidFile { message, .. } => message.clone(),
+            Self::InvalidRecord {
+                field: Some(field),
+                message,
+                ..
+            } => format!("`{}` is not valid ({})", field, message),
+            Self::InvalidRecord {
+                field: None,
+                message,
+                ..
+            } => message.clone(),
+        }
+    }
+
+    /// Returns the name of the file the error occurred in.
+    pub fn file(&self) -> &str {
+        match self {
+            Self::InvalidArchive { file, .. }
+            | Self::InvalidFile { file, .. }
+            | Self::InvalidRecord { file, .. } => file,
+        }
+    }
+
+    /// Sets the name of the file the error occurred in.
+    pub fn in_file(mut self, name: impl Into<String>) -> Self {
+        match &mut self {
+            Self::InvalidArchive { file, .. }
+            | Self::InvalidFile { file, .. }
+            | Self::InvalidRecord { file, .. } => *file = name.into(),
+        }
+        self
+    }
+}
+
+/// Returns the message of a `serde_json::Error` without the "at line x column y" suffix.
+fn serde_message(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    message
+        .strip_suffix(&position)
+        .unwrap_or(&message)
+        .to_string()
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidArchive { file, .. } | Self::InvalidFile { file, .. } => {
+                write!(f, "{}: {}", file, self.reason())
+            }
+            Self::InvalidRecord { file, index, .. } => write!(
+                f,
+                "{}, record {}: {}",
+                file,
+                with_thousands_separator(index + 1),
+                self.reason()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+    use crate::data_parser::playback_record::PlaybackRecord;
+
+    const json: &str = include_str!("./test/test_data.json");
+
+    fn parse_error(input: &str) -> ParseError {
+        PlaybackRecord::from_json(input.as_bytes()).unwrap_err()
+    }
+
+    #[test]
+    fn test_invalid_field() {
+        let broken = json.replacen("\"ts\":\"2023-10-22T00:00:00Z\"", "\"ts\":\"yesterday\"", 1);
+        match parse_error(&broken) {
+            ParseError::InvalidRecord { index, field, .. } => {
+                assert_eq!(index, 0);
+                assert_eq!(field.as_deref(), Some("ts"));
+            }
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let error = parse_error(r#"[{"ts":"2023-10-22T00:00:00Z"}]"#);
+        match error {
+            ParseError::InvalidRecord { index, field, .. } => {
+                assert_eq!(index, 0);
+                assert_eq!(field.as_deref(), Some("platform"));
+            }
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        assert!(matches!(parse_error("{}"), ParseError::InvalidFile { .. }));
+        assert!(matches!(
+            parse_error("[] []"),
+            ParseError::InvalidFile { .. }
+        ));
+    }
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::InvalidRecord {
+            file: String::new(),
+            index: 1481,
+            field: Some("ts".to_string()),
+            message: "input contains invalid characters".to_string(),
+        }
+        .in_file("Streaming_History_Audio_2023_3.json");
+        assert_eq!(
+            error.to_string(),
+            "Streaming_History_Audio_2023_3.json, record 1,482: `ts` is not valid (input contains invalid characters)"
+        );
+    }
+}
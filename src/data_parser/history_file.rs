@@ -0,0 +1,110 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    archive::{is_zip, ACCOUNT_DATA_PREFIX, EXTENDED_AUDIO_PREFIX, EXTENDED_VIDEO_PREFIX},
+    playback_record::HistorySchema,
+};
+
+/// A streaming history file uploaded by the user, or an entry extracted from an uploaded archive.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryFile {
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+/// What an uploaded file contains, as far as it can be told without parsing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// An extended streaming history file of music and podcasts (`Streaming_History_Audio_*`).
+    ExtendedAudio,
+    /// An extended streaming history file of videos (`Streaming_History_Video_*`).
+    ExtendedVideo,
+    /// A file of the "Account data" export (`StreamingHistory_music_*`).
+    AccountData,
+    /// A ZIP archive, e.g. the whole `my_spotify_data.zip`.
+    Archive,
+    /// Anything else. These files cannot be imported.
+    Unknown,
+}
+
+impl HistoryFile {
+    pub fn new(name: impl Into<String>, contents: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            contents,
+        }
+    }
+
+    pub fn kind(&self) -> FileKind {
+        FileKind::detect(&self.name, &self.contents)
+    }
+}
+
+impl FileKind {
+    /// Detects the kind of a file from its contents, falling back to its name for files without
+    /// records. The name may be prefixed with the archive the file was extracted from.
+    pub fn detect(name: &str, contents: &[u8]) -> Self {
+        let name = name.rsplit('/').next().unwrap_or(name);
+        if is_zip(contents) {
+            return Self::Archive;
+        }
+        match HistorySchema::detect(contents) {
+            Some(HistorySchema::AccountData) => Self::AccountData,
+            Some(HistorySchema::Extended) if name.starts_with(EXTENDED_VIDEO_PREFIX) => {
+                Self::ExtendedVideo
+            }
+            Some(HistorySchema::Extended) => Self::ExtendedAudio,
+            None if name.starts_with(EXTENDED_AUDIO_PREFIX) => Self::ExtendedAudio,
+            None if name.starts_with(EXTENDED_VIDEO_PREFIX) => Self::ExtendedVideo,
+            None if name.starts_with(ACCOUNT_DATA_PREFIX) => Self::AccountData,
+            None => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for FileKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::ExtendedAudio => "Extended history (audio)",
+            Self::ExtendedVideo => "Extended history (video)",
+            Self::AccountData => "Account data",
+            Self::Archive => "ZIP archive",
+            Self::Unknown => "Unknown",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_history_file {
+    use super::*;
+    const json: &str = include_str!("./test/test_data.json");
+
+    #[test]
+    fn test_kind() {
+        let audio = HistoryFile::new("history.json", json.as_bytes().to_vec());
+        assert_eq!(audio.kind(), FileKind::ExtendedAudio);
+        let video = HistoryFile::new(
+            "Streaming_History_Video_2023.json",
+            json.as_bytes().to_vec(),
+        );
+        assert_eq!(video.kind(), FileKind::ExtendedVideo);
+        let account_data = HistoryFile::new(
+            "history.json",
+            br#"[{"endTime":"2023-10-22 13:45","artistName":"a","trackName":"b","msPlayed":1}]"#
+                .to_vec(),
+        );
+        assert_eq!(account_data.kind(), FileKind::AccountData);
+        let archive = HistoryFile::new("my_spotify_data.zip", b"PK\x03\x04".to_vec());
+        assert_eq!(archive.kind(), FileKind::Archive);
+    }
+
+    #[test]
+    fn test_kind_of_empty_file() {
+        let empty_video = HistoryFile::new("Streaming_History_Video_2023.json", b"[]".to_vec());
+        assert_eq!(empty_video.kind(), FileKind::ExtendedVideo);
+        let unrelated = HistoryFile::new("Userdata.json", br#"{"username":"x"}"#.to_vec());
+        assert_eq!(unrelated.kind(), FileKind::Unknown);
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::playback_record::PlaybackRecord;
+
+/// Minimum playback (in ms) for a stream to count as a qualified stream. Spotify only counts the
+/// streams of at least 30 seconds.
+pub const QUALIFIED_STREAM_MS: u32 = 30_000;
+
+/// What the rankings are measured with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Metric {
+    /// The total playback duration, in ms.
+    #[default]
+    Time,
+    /// The number of streams.
+    Streams,
+    /// The number of streams of at least [`QUALIFIED_STREAM_MS`].
+    QualifiedStreams,
+}
+
+impl Metric {
+    pub const ALL: [Self; 3] = [Self::Time, Self::Streams, Self::QualifiedStreams];
+
+    /// Measures `records` with this metric.
+    pub fn measure(&self, records: &[PlaybackRecord]) -> u64 {
+        match self {
+            Self::Time => records.iter().map(|record| record.ms_played as u64).sum(),
+            Self::Streams => records.len() as u64,
+            Self::QualifiedStreams => records
+                .iter()
+                .filter(|record| record.ms_played >= QUALIFIED_STREAM_MS)
+                .count() as u64,
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Time => write!(f, "Listening time"),
+            Self::Streams => write!(f, "Streams"),
+            Self::QualifiedStreams => {
+                write!(f, "Streams over {} s", QUALIFIED_STREAM_MS / 1000)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_metric {
+    use super::*;
+    use crate::data_parser::{queries::get_top_artists, test_records::record};
+
+    #[test]
+    fn test_measure() {
+        let short = record().ms_played(QUALIFIED_STREAM_MS - 1).build();
+        let qualified = record().ms_played(QUALIFIED_STREAM_MS).build();
+        let records = vec![short.clone(), qualified.clone(), short];
+        assert_eq!(Metric::Time.measure(&records), 89_998);
+        assert_eq!(Metric::Streams.measure(&records), 3);
+        assert_eq!(Metric::QualifiedStreams.measure(&records), 1);
+        assert_eq!(Metric::QualifiedStreams.measure(&[qualified]), 1);
+        assert_eq!(Metric::Streams.measure(&[]), 0);
+    }
+
+    #[test]
+    fn test_ranking_depends_on_metric() {
+        // A is played once for ten minutes, B three times just short of a qualified stream.
+        let mut records = vec![record().artist("A").minutes(10).build()];
+        for _ in 0..3 {
+            records.push(
+                record()
+                    .artist("B")
+                    .ms_played(QUALIFIED_STREAM_MS - 1)
+                    .build(),
+            );
+        }
+        let first = |metric: Metric| get_top_artists(&records, metric)[0].0.clone();
+        assert_eq!(first(Metric::Time), "A");
+        assert_eq!(first(Metric::Streams), "B");
+        assert_eq!(first(Metric::QualifiedStreams), "A");
+    }
+}
@@ -1,3 +1,24 @@
+pub mod archive;
+pub mod calendar;
+pub mod chart;
+pub mod comparison;
+pub mod content;
+pub mod dataset;
+pub mod discovery;
+pub mod error;
+pub mod history_file;
+pub mod metric;
+pub mod period;
 pub mod playback_record;
 pub mod processing;
 pub mod queries;
+pub mod rank_history;
+pub mod report;
+pub mod sessions;
+pub mod skips;
+pub mod streaks;
+pub mod streaming;
+#[cfg(test)]
+pub(crate) mod test_records;
+pub mod timezone;
+pub mod track;
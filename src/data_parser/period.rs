@@ -0,0 +1,92 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// The period of time the statistics are computed for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Period {
+    /// The whole listening history.
+    #[default]
+    AllTime,
+    /// A single calendar year.
+    Year(i32),
+    /// A custom range of days. Both `start` and `end` are included.
+    Range { start: NaiveDate, end: NaiveDate },
+}
+
+impl Period {
+    /// Returns `true` if a stream that ended at `ts` lies within this period, in the timezone of
+    /// `ts`.
+    pub fn contains<Tz: TimeZone>(&self, ts: &DateTime<Tz>) -> bool {
+        match self {
+            Self::AllTime => true,
+            Self::Year(year) => ts.year() == *year,
+            Self::Range { start, end } => (*start..=*end).contains(&ts.date_naive()),
+        }
+    }
+
+    /// Returns the first and the last day of this period, `None` for [`Period::AllTime`] which
+    /// is only bounded by the history itself.
+    pub fn bounds(&self) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            Self::AllTime => None,
+            Self::Year(year) => Some((
+                NaiveDate::from_ymd_opt(*year, 1, 1)?,
+                NaiveDate::from_ymd_opt(*year, 12, 31)?,
+            )),
+            Self::Range { start, end } => Some((*start, *end)),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AllTime => write!(f, "All time"),
+            Self::Year(year) => write!(f, "{}", year),
+            Self::Range { start, end } => write!(
+                f,
+                "{} – {}",
+                start.format("%d/%m/%y"),
+                end.format("%d/%m/%y")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_period {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_contains() {
+        let ts = Utc.with_ymd_and_hms(2019, 12, 31, 23, 59, 0).unwrap();
+        assert!(Period::AllTime.contains(&ts));
+        assert!(Period::Year(2019).contains(&ts));
+        assert!(!Period::Year(2020).contains(&ts));
+        assert!(Period::Range {
+            start: NaiveDate::from_ymd_opt(2019, 12, 1).unwrap(),
+            end: NaiveDate::from_ymd_opt(2019, 12, 31).unwrap(),
+        }
+        .contains(&ts));
+        assert!(!Period::Range {
+            start: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+            end: NaiveDate::from_ymd_opt(2020, 12, 31).unwrap(),
+        }
+        .contains(&ts));
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(Period::AllTime.bounds(), None);
+        assert_eq!(
+            Period::Year(2020).bounds(),
+            Some((
+                NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2020, 12, 31).unwrap()
+            ))
+        );
+    }
+}
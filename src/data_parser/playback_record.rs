@@ -4,7 +4,94 @@ use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use gloo::{console::log, utils::format};
 use rayon::prelude::*;
 use serde::{ser, Deserialize, Serialize};
-use serde_json::{from_slice, from_value, Deserializer, Value};
+use serde_json::{Deserializer, Map, Value};
+
+use super::{
+    error::ParseError,
+    history_file::HistoryFile,
+    processing::deduplicate,
+    report::{FileReport, ImportReport},
+    streaming::{self, ParseMode},
+};
+
+/// The export format a streaming history file was downloaded in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HistorySchema {
+    /// The "Extended streaming history" export (`Streaming_History_Audio_*.json`).
+    #[default]
+    Extended,
+    /// The quick "Account data" export (`StreamingHistory_music_*.json`). It only contains the
+    /// end time, artist, track and playback duration of every stream.
+    AccountData,
+}
+
+impl HistorySchema {
+    /// Detects the schema of a streaming history file by looking at the keys of its first record.
+    ///
+    /// Returns `None` if the file is empty or its first record matches neither schema.
+    pub fn detect(json: &[u8]) -> Option<Self> {
+        let start = json.iter().position(|byte| *byte == b'{')?;
+        let mut deserializer = Deserializer::from_slice(&json[start..]);
+        let first_record: Map<String, Value> = Map::deserialize(&mut deserializer).ok()?;
+        if first_record.contains_key("ts") {
+            Some(Self::Extended)
+        } else if first_record.contains_key("endTime") {
+            Some(Self::AccountData)
+        } else {
+            None
+        }
+    }
+}
+
+/// The kind of content a stream played.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ContentType {
+    /// A song.
+    Track,
+    /// A podcast episode.
+    Episode,
+    /// A chapter of an audiobook.
+    Audiobook,
+    /// The export does not tell, e.g. the account data export.
+    #[default]
+    Unknown,
+}
+
+impl ContentType {
+    /// Reads the content type from the scheme of a Spotify URI, e.g. `spotify:episode:…`.
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        match uri.split(':').take(2).collect::<Vec<_>>()[..] {
+            ["spotify", "track"] => Some(Self::Track),
+            ["spotify", "episode"] => Some(Self::Episode),
+            _ => None,
+        }
+    }
+
+    /// Detects the content type of a record of the extended streaming history. Audiobook chapters
+    /// are recognized by their audiobook fields, other records by the scheme of their URI or, if
+    /// it is missing or unknown, by the name fields that are set.
+    fn detect(raw: &UnsanitizedPlaybackRecord) -> Self {
+        if raw.audiobook_title.is_some() || raw.audiobook_chapter_uri.is_some() {
+            return Self::Audiobook;
+        }
+        raw.spotify_track_uri
+            .iter()
+            .chain(raw.spotify_episode_uri.iter())
+            .find_map(|uri| Self::from_uri(uri))
+            .unwrap_or(if raw.master_metadata_track_name.is_some() {
+                Self::Track
+            } else if raw.episode_name.is_some() {
+                Self::Episode
+            } else {
+                Self::Unknown
+            })
+    }
+
+    /// Returns `true` for songs and for records of unknown type, which are mostly songs.
+    pub fn is_music(&self) -> bool {
+        matches!(self, Self::Track | Self::Unknown)
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PlaybackRecord {
@@ -16,7 +103,16 @@ pub struct PlaybackRecord {
     pub master_metadata_track_name: String,
     pub master_metadata_album_artist_name: String,
     pub master_metadata_album_album_name: String,
+    /// The URI of the track or, for podcasts, of the episode.
     pub spotify_track_uri: String,
+    /// The name of the podcast episode, empty for other content.
+    #[serde(default)]
+    pub podcast_episode_name: String,
+    /// The name of the show of the podcast episode, empty for other content.
+    #[serde(default)]
+    pub podcast_show_name: String,
+    #[serde(default)]
+    pub content_type: ContentType,
     pub reason_start: String,
     pub reason_end: String,
     pub shuffle: bool,
@@ -24,10 +120,18 @@ pub struct PlaybackRecord {
     pub offline: bool,
     pub offline_timestamp: Option<u64>,
     pub incognito_mode: bool,
+    /// The schema this record was read from. Fields missing from that schema are left empty
+    /// (or `false`) and should be treated as unknown.
+    #[serde(default)]
+    pub schema: HistorySchema,
+    /// The names of the files this record was imported from, in the order they were imported.
+    /// Empty if they are not known.
+    #[serde(default)]
+    pub sources: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-struct UnsanitizedPlaybackRecord {
+pub(crate) struct UnsanitizedPlaybackRecord {
     pub ts: DateTime<Utc>,
     pub platform: String,
     pub ms_played: u32,
@@ -40,6 +144,8 @@ struct UnsanitizedPlaybackRecord {
     pub episode_name: Option<String>,
     pub episode_show_name: Option<String>,
     pub spotify_episode_uri: Option<String>,
+    pub audiobook_title: Option<String>,
+    pub audiobook_chapter_uri: Option<String>,
     pub reason_start: String,
     pub reason_end: String,
     pub shuffle: bool,
@@ -49,19 +155,43 @@ struct UnsanitizedPlaybackRecord {
     pub incognito_mode: bool,
 }
 
-impl UnsanitizedPlaybackRecord {
-    pub fn from_json(json: &[u8]) -> Result<Vec<Self>, serde_json::Error> {
-        let json_values: Vec<serde_json::Value> = from_slice(json)?;
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountDataPlaybackRecord {
+    #[serde(with = "account_data_time")]
+    pub end_time: DateTime<Utc>,
+    pub artist_name: String,
+    pub track_name: String,
+    pub ms_played: u32,
+}
 
-        json_values
-            .into_par_iter()
-            .map(|value| from_value(value))
-            .collect()
+/// (De)serializes the `endTime` field of the account data export, e.g. `"2023-10-22 13:45"` (UTC).
+mod account_data_time {
+    use chrono::{DateTime, NaiveDateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    const FORMAT: &str = "%Y-%m-%d %H:%M";
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.format(FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        NaiveDateTime::parse_from_str(&raw, FORMAT)
+            .map(|time| time.and_utc())
+            .map_err(de::Error::custom)
     }
 }
 
 impl From<UnsanitizedPlaybackRecord> for PlaybackRecord {
     fn from(raw: UnsanitizedPlaybackRecord) -> Self {
+        let content_type = ContentType::detect(&raw);
         PlaybackRecord {
             ts: raw.ts,
             platform: raw.platform,
@@ -70,11 +200,9 @@ impl From<UnsanitizedPlaybackRecord> for PlaybackRecord {
             ip_addr: raw.ip_addr,
             master_metadata_track_name: raw
                 .master_metadata_track_name
-                .or_else(|| raw.episode_name.clone())
                 .unwrap_or_else(|| "".to_string()),
             master_metadata_album_artist_name: raw
                 .master_metadata_album_artist_name
-                .or_else(|| raw.episode_show_name.clone())
                 .unwrap_or_else(|| "".to_string()),
             master_metadata_album_album_name: raw
                 .master_metadata_album_album_name
@@ -83,6 +211,9 @@ impl From<UnsanitizedPlaybackRecord> for PlaybackRecord {
                 .spotify_track_uri
                 .or_else(|| raw.spotify_episode_uri)
                 .unwrap_or_else(|| "".to_string()),
+            podcast_episode_name: raw.episode_name.unwrap_or_else(|| "".to_string()),
+            podcast_show_name: raw.episode_show_name.unwrap_or_else(|| "".to_string()),
+            content_type,
             reason_start: raw.reason_start,
             reason_end: raw.reason_end,
             shuffle: raw.shuffle,
@@ -90,29 +221,346 @@ impl From<UnsanitizedPlaybackRecord> for PlaybackRecord {
             offline: raw.offline,
             offline_timestamp: raw.offline_timestamp,
             incognito_mode: raw.incognito_mode,
+            schema: HistorySchema::Extended,
+            sources: vec![],
         }
     }
 }
+
+impl From<AccountDataPlaybackRecord> for PlaybackRecord {
+    fn from(raw: AccountDataPlaybackRecord) -> Self {
+        PlaybackRecord {
+            ts: raw.end_time,
+            platform: "".to_string(),
+            ms_played: raw.ms_played,
+            conn_country: "".to_string(),
+            ip_addr: "".to_string(),
+            master_metadata_track_name: raw.track_name,
+            master_metadata_album_artist_name: raw.artist_name,
+            master_metadata_album_album_name: "".to_string(),
+            spotify_track_uri: "".to_string(),
+            podcast_episode_name: "".to_string(),
+            podcast_show_name: "".to_string(),
+            content_type: ContentType::Unknown,
+            reason_start: "".to_string(),
+            reason_end: "".to_string(),
+            shuffle: false,
+            skipped: false,
+            offline: false,
+            offline_timestamp: None,
+            incognito_mode: false,
+            schema: HistorySchema::AccountData,
+            sources: vec![],
+        }
+    }
+}
+/// The fields that identify a stream across exports. Two records with the same identity are the
+/// same stream, e.g. because it is contained in two exports requested at different dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordIdentity<'a> {
+    pub ts: DateTime<Utc>,
+    pub spotify_track_uri: &'a str,
+    pub ms_played: u32,
+    pub platform: &'a str,
+}
+
 impl PlaybackRecord {
-    pub fn from_json(json: &[u8]) -> Result<Vec<PlaybackRecord>, serde_json::Error> {
-        let unsanitized = UnsanitizedPlaybackRecord::from_json(json);
-        if let Err(e) = unsanitized {
-            return Err(e);
+    pub fn identity(&self) -> RecordIdentity<'_> {
+        RecordIdentity {
+            ts: self.ts,
+            spotify_track_uri: &self.spotify_track_uri,
+            ms_played: self.ms_played,
+            platform: &self.platform,
         }
-        Ok(unsanitized
-            .unwrap_or(vec![])
-            .into_par_iter()
-            .map(|ur| PlaybackRecord::from(ur))
-            .collect())
     }
-    pub fn from_jsons(jsons: &[Vec<u8>]) -> Result<Vec<PlaybackRecord>, serde_json::Error> {
-        let data_files: Result<Vec<Vec<PlaybackRecord>>, serde_json::Error> = jsons
+    /// Returns `true` if every field of this record is known, i.e. it was read from the extended
+    /// streaming history.
+    pub fn has_extended_fields(&self) -> bool {
+        self.schema == HistorySchema::Extended
+    }
+    pub fn from_json(json: &[u8]) -> Result<Vec<PlaybackRecord>, ParseError> {
+        PlaybackRecord::from_json_with_progress(json, |_| ())
+    }
+    /// Parses a single streaming history file record by record, calling `on_progress` with the
+    /// number of records parsed so far. The schema of the file is detected automatically.
+    pub fn from_json_with_progress<F>(
+        json: &[u8],
+        on_progress: F,
+    ) -> Result<Vec<PlaybackRecord>, ParseError>
+    where
+        F: FnMut(usize),
+    {
+        PlaybackRecord::parse_json(json, ParseMode::Strict, on_progress).map(|(records, _)| records)
+    }
+    /// Like [`PlaybackRecord::from_json_with_progress`], but in [`ParseMode::Lenient`] the records
+    /// that cannot be read are skipped and returned alongside the parsed ones.
+    pub fn parse_json<F>(
+        json: &[u8],
+        mode: ParseMode,
+        on_progress: F,
+    ) -> Result<(Vec<PlaybackRecord>, Vec<ParseError>), ParseError>
+    where
+        F: FnMut(usize),
+    {
+        match HistorySchema::detect(json) {
+            Some(HistorySchema::AccountData) => {
+                streaming::parse_records::<AccountDataPlaybackRecord, _>(json, mode, on_progress)
+            }
+            _ => streaming::parse_records::<UnsanitizedPlaybackRecord, _>(json, mode, on_progress),
+        }
+    }
+    /// Parses several streaming history files, dropping records that occur in more than one of
+    /// them. Errors name the file by its position, e.g. `file 3`.
+    pub fn from_jsons(jsons: &[Vec<u8>]) -> Result<Vec<PlaybackRecord>, ParseError> {
+        let data_files: Result<Vec<Vec<PlaybackRecord>>, ParseError> = jsons
             .into_par_iter()
-            .map(|json| PlaybackRecord::from_json(json))
+            .enumerate()
+            .map(|(i, json)| {
+                PlaybackRecord::from_json(json).map_err(|e| e.in_file(format!("file {}", i + 1)))
+            })
             .collect();
         if let Err(e) = data_files {
             return Err(e);
         }
-        Ok(data_files.unwrap_or(vec![]).into_iter().flatten().collect())
+        let (records, _) =
+            deduplicate(data_files.unwrap_or(vec![]).into_iter().flatten().collect());
+        Ok(records)
+    }
+    /// Parses several streaming history files, dropping records that occur in more than one of
+    /// them. The copy that is kept lists every file the record occurs in. Errors name the file
+    /// they occurred in.
+    pub fn from_files(files: &[HistoryFile]) -> Result<Vec<PlaybackRecord>, ParseError> {
+        PlaybackRecord::from_files_with_mode(files, ParseMode::Strict).map(|(records, _)| records)
+    }
+    /// Parses several streaming history files, skipping unreadable records in
+    /// [`ParseMode::Lenient`].
+    ///
+    /// # Returns
+    ///
+    /// The deduplicated records of all files and an [`ImportReport`] listing the skipped ones
+    /// and the number of duplicates, or the first error that made a whole file unreadable.
+    pub fn from_files_with_mode(
+        files: &[HistoryFile],
+        mode: ParseMode,
+    ) -> Result<(Vec<PlaybackRecord>, ImportReport), ParseError> {
+        PlaybackRecord::from_files_with_progress(files, mode, |_| ())
+    }
+    /// Like [`PlaybackRecord::from_files_with_mode`], but calls `on_progress` with the number of
+    /// records parsed so far from all files together. The files are parsed one after the other, so
+    /// `on_progress` does not need to be thread safe.
+    pub fn from_files_with_progress<F>(
+        files: &[HistoryFile],
+        mode: ParseMode,
+        mut on_progress: F,
+    ) -> Result<(Vec<PlaybackRecord>, ImportReport), ParseError>
+    where
+        F: FnMut(usize),
+    {
+        let mut parsed_before = 0;
+        let data_files: Result<Vec<(Vec<PlaybackRecord>, ImportReport)>, ParseError> = files
+            .iter()
+            .map(|file| {
+                let (records, report) = PlaybackRecord::from_file_with_progress(file, mode, |n| {
+                    on_progress(parsed_before + n)
+                })?;
+                parsed_before += records.len() + report.skipped();
+                Ok((records, report))
+            })
+            .collect();
+        Ok(PlaybackRecord::combine(data_files?))
+    }
+    /// Parses a single file of an import, calling `on_progress` with the number of records parsed
+    /// so far from it. The records are not deduplicated, see [`PlaybackRecord::combine`].
+    pub fn from_file_with_progress<F>(
+        file: &HistoryFile,
+        mode: ParseMode,
+        on_progress: F,
+    ) -> Result<(Vec<PlaybackRecord>, ImportReport), ParseError>
+    where
+        F: FnMut(usize),
+    {
+        let (mut records, rejected) = PlaybackRecord::parse_json(&file.contents, mode, on_progress)
+            .map_err(|e| e.in_file(&file.name))?;
+        for record in records.iter_mut() {
+            record.sources = vec![file.name.clone()];
+        }
+        let report = ImportReport {
+            imported: records.len(),
+            files: vec![FileReport {
+                file: file.name.clone(),
+                imported: records.len(),
+                rejected: rejected.len(),
+                duplicates: 0,
+            }],
+            rejected: rejected
+                .into_iter()
+                .map(|e| e.in_file(&file.name))
+                .collect(),
+            duplicates: 0,
+        };
+        Ok((records, report))
+    }
+    /// Combines the files parsed by [`PlaybackRecord::from_file_with_progress`] into the records
+    /// of an import, dropping the records that several files contain.
+    pub fn combine(
+        files: impl IntoIterator<Item = (Vec<PlaybackRecord>, ImportReport)>,
+    ) -> (Vec<PlaybackRecord>, ImportReport) {
+        let (records, report) = files.into_iter().fold(
+            (vec![], ImportReport::default()),
+            |(mut all_records, all_reports), (records, report)| {
+                all_records.extend(records);
+                (all_records, all_reports.merge(report))
+            },
+        );
+        let (records, _) = deduplicate(records);
+        let report = report.with_kept(&records);
+        (records, report)
+    }
+}
+
+#[cfg(test)]
+mod test_playback_record {
+    use super::*;
+    const json: &str = include_str!("./test/test_data.json");
+    const account_data_json: &str = r#"[
+        {"endTime":"2023-10-22 13:45","artistName":"Heidi Barabisch","trackName":"Ms","msPlayed":31545},
+        {"endTime":"2023-10-23 08:02","artistName":"Pearla Jodrelle","trackName":"Rev","msPlayed":25664}
+    ]"#;
+
+    #[test]
+    fn test_detect_schema() {
+        assert_eq!(
+            HistorySchema::detect(json.as_bytes()),
+            Some(HistorySchema::Extended)
+        );
+        assert_eq!(
+            HistorySchema::detect(account_data_json.as_bytes()),
+            Some(HistorySchema::AccountData)
+        );
+        assert_eq!(HistorySchema::detect(b"[]"), None);
+    }
+
+    #[test]
+    fn test_from_json_account_data() {
+        let records = PlaybackRecord::from_json(account_data_json.as_bytes()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].ts,
+            Utc.with_ymd_and_hms(2023, 10, 22, 13, 45, 0).unwrap()
+        );
+        assert_eq!(records[0].master_metadata_track_name, "Ms");
+        assert_eq!(
+            records[0].master_metadata_album_artist_name,
+            "Heidi Barabisch"
+        );
+        assert_eq!(records[0].ms_played, 31545);
+        assert!(records.iter().all(|record| !record.has_extended_fields()));
+    }
+
+    #[test]
+    fn test_from_json_extended() {
+        let records = PlaybackRecord::from_json(json.as_bytes()).unwrap();
+        assert!(records.iter().all(|record| record.has_extended_fields()));
+    }
+
+    #[test]
+    fn test_content_type() {
+        let podcast = r#"[{"ts":"2023-10-22T00:00:00Z","platform":"android","ms_played":25664,
+            "conn_country":"CN","ip_addr":"130.66.192.31","master_metadata_track_name":null,
+            "master_metadata_album_artist_name":null,"master_metadata_album_album_name":null,
+            "spotify_track_uri":null,"episode_name":"Episode 1","episode_show_name":"The Show",
+            "spotify_episode_uri":"spotify:episode:1","reason_start":"play","reason_end":"skip",
+            "shuffle":false,"skipped":true,"offline":false,"offline_timestamp":737461530,
+            "incognito_mode":false}]"#;
+        let records = PlaybackRecord::from_json(podcast.as_bytes()).unwrap();
+        assert_eq!(records[0].content_type, ContentType::Episode);
+        assert_eq!(records[0].podcast_show_name, "The Show");
+        assert_eq!(records[0].master_metadata_album_artist_name, "");
+
+        let audiobook = podcast.replace(
+            r#""spotify_episode_uri""#,
+            r#""audiobook_title":"The Book","spotify_episode_uri""#,
+        );
+        let records = PlaybackRecord::from_json(audiobook.as_bytes()).unwrap();
+        assert_eq!(records[0].content_type, ContentType::Audiobook);
+
+        let records = PlaybackRecord::from_json(json.as_bytes()).unwrap();
+        assert!(records
+            .iter()
+            .all(|record| record.content_type == ContentType::Track));
+        let records = PlaybackRecord::from_json(account_data_json.as_bytes()).unwrap();
+        assert!(records
+            .iter()
+            .all(|record| record.content_type == ContentType::Unknown));
+        assert_eq!(
+            ContentType::from_uri("spotify:track:4uLU6hMCjMI75M1A2tKUQC"),
+            Some(ContentType::Track)
+        );
+        assert_eq!(ContentType::from_uri("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_from_files_drops_duplicates() {
+        // The duplicates of the second file are dropped, their source is added to the first copy.
+        let records: Vec<PlaybackRecord> = PlaybackRecord::from_json(json.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|record| PlaybackRecord {
+                sources: vec![
+                    "Streaming_History_Audio_2023_0.json".to_string(),
+                    "Streaming_History_Audio_2023_1.json".to_string(),
+                ],
+                ..record
+            })
+            .collect();
+        let files = vec![
+            HistoryFile::new(
+                "Streaming_History_Audio_2023_0.json",
+                json.as_bytes().to_vec(),
+            ),
+            HistoryFile::new(
+                "Streaming_History_Audio_2023_1.json",
+                json.as_bytes().to_vec(),
+            ),
+        ];
+        let (deduplicated, report) =
+            PlaybackRecord::from_files_with_mode(&files, ParseMode::Strict).unwrap();
+        assert_eq!(deduplicated, records);
+        assert_eq!(report.imported, records.len());
+        assert_eq!(report.duplicates, records.len());
+        assert_eq!(report.files[0].imported, records.len());
+        assert_eq!(report.files[1].imported, 0);
+        assert_eq!(report.files[1].duplicates, records.len());
+    }
+
+    #[test]
+    fn test_from_files_reports_progress_across_files() {
+        let files = vec![
+            HistoryFile::new("first.json", json.as_bytes().to_vec()),
+            HistoryFile::new("second.json", account_data_json.as_bytes().to_vec()),
+        ];
+        let mut reports = vec![];
+        let (records, _) =
+            PlaybackRecord::from_files_with_progress(&files, ParseMode::Strict, |n| {
+                reports.push(n)
+            })
+            .unwrap();
+        assert_eq!(reports.last(), Some(&records.len()));
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_from_files_sets_source() {
+        let files = vec![
+            HistoryFile::new("first.json", json.as_bytes().to_vec()),
+            HistoryFile::new("second.json", account_data_json.as_bytes().to_vec()),
+        ];
+        let (records, _) = PlaybackRecord::from_files_with_mode(&files, ParseMode::Strict).unwrap();
+        assert!(records
+            .iter()
+            .all(|record| (record.sources == ["first.json"]) == record.has_extended_fields()));
+        assert!(records
+            .iter()
+            .any(|record| record.sources == ["second.json"]));
     }
 }
@@ -1,7 +1,11 @@
 use binary_heap_plus::BinaryHeap;
-use std::{cmp::Ordering, collections::HashMap, hash::Hash};
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
 
-use super::playback_record::PlaybackRecord;
+use super::playback_record::{PlaybackRecord, RecordIdentity};
 
 /// Groups a vector of `PlaybackRecord` objects by a key-generating function.
 ///
@@ -162,6 +166,78 @@ where
         .collect()
 }
 
+/// Removes duplicate `PlaybackRecord` objects from a vector, keeping the first occurrence. The
+/// sources of the removed duplicates are added to the one that is kept.
+///
+/// # Arguments
+///
+/// * `data` - The vector of `PlaybackRecord` objects to deduplicate.
+///
+/// # Returns
+///
+/// A `(records, duplicates)` tuple where `records` keeps the original order and `duplicates` is
+/// the number of records that were removed.
+///
+/// # Notes
+///
+/// Two records are duplicates if they share the same
+/// [`RecordIdentity`](super::playback_record::RecordIdentity).
+pub(crate) fn deduplicate(data: Vec<PlaybackRecord>) -> (Vec<PlaybackRecord>, usize) {
+    let total = data.len();
+    let mut records = Vec::with_capacity(total);
+    let duplicates = append_records(&mut records, data);
+    (records, duplicates)
+}
+
+/// Adds the records of `data` to `known`, e.g. when the records of another export are added to
+/// the dataset. A record `known` already contains is not added again, the sources of its new copy
+/// are added to the known one instead, so that it is kept as long as any of its files is.
+///
+/// # Arguments
+///
+/// * `known` - The records that are already imported.
+/// * `data` - The new records.
+///
+/// # Returns
+///
+/// The number of records of `data` that `known` already contained.
+pub fn append_records(known: &mut Vec<PlaybackRecord>, data: Vec<PlaybackRecord>) -> usize {
+    // The position in `known` every record of `data` is a copy of, `None` for the new ones.
+    let copy_of: Vec<Option<usize>> = {
+        let mut positions: HashMap<RecordIdentity, usize> = HashMap::with_capacity(known.len());
+        for (i, record) in known.iter().enumerate() {
+            positions.entry(record.identity()).or_insert(i);
+        }
+        let mut next = known.len();
+        data.iter()
+            .map(|record| match positions.entry(record.identity()) {
+                Entry::Occupied(entry) => Some(*entry.get()),
+                Entry::Vacant(entry) => {
+                    entry.insert(next);
+                    next += 1;
+                    None
+                }
+            })
+            .collect()
+    };
+    let mut duplicates = 0;
+    for (record, copy_of) in data.into_iter().zip(copy_of) {
+        match copy_of {
+            Some(i) => {
+                duplicates += 1;
+                let sources = &mut known[i].sources;
+                for source in record.sources {
+                    if !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
+            }
+            None => known.push(record),
+        }
+    }
+    duplicates
+}
+
 #[cfg(test)]
 mod test_group_by {
     use std::fmt::Debug;
@@ -341,4 +417,35 @@ mod test_filter {
     fn test_filter_by_platform_linux() {
         test_filter_by(|record| record.platform == "android");
     }
+
+    #[test]
+    fn test_append_records() {
+        let data = get_test_data();
+        let (known, new) = data.split_at(data.len() / 2);
+        let mut records = known.to_vec();
+        let duplicates = append_records(&mut records, data.clone());
+        assert_eq!(records, data);
+        assert_eq!(&records[known.len()..], new);
+        assert_eq!(duplicates, known.len());
+    }
+
+    #[test]
+    fn test_append_records_merges_sources() {
+        let with_source = |source: &str| -> Vec<PlaybackRecord> {
+            get_test_data()
+                .into_iter()
+                .map(|record| PlaybackRecord {
+                    sources: vec![source.to_string()],
+                    ..record
+                })
+                .collect()
+        };
+        let mut records = with_source("a.json");
+        append_records(&mut records, with_source("b.json"));
+        append_records(&mut records, with_source("b.json"));
+        assert_eq!(records.len(), get_test_data().len());
+        assert!(records
+            .iter()
+            .all(|record| record.sources == ["a.json", "b.json"]));
+    }
 }
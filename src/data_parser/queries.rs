@@ -1,44 +1,157 @@
-use std::{hash::Hash, i64, str::FromStr, u64};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::Hash,
+    i64,
+    str::FromStr,
+    u64,
+};
 
 use chrono::prelude::*;
 use rayon::iter::IntoParallelIterator;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    playback_record::PlaybackRecord,
+    content::ContentFilter,
+    metric::Metric,
+    period::Period,
+    playback_record::{ContentType, PlaybackRecord},
     processing::{filter_by, group_by, sort_by_top_n},
+    sessions::DEFAULT_SESSION_GAP_MINUTES,
+    timezone::Timezone,
+    track::{Album, Track, TrackGrouping},
 };
+
+/// Settings that change how the queries of a chart are computed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QueryOptions {
+    /// The kinds of content the records are filtered on before querying them.
+    pub content: ContentFilter,
+    /// How the streams are attributed to songs.
+    pub tracks: TrackGrouping,
+    /// What the rankings are measured with.
+    pub metric: Metric,
+    /// The break (in minutes) after which the next stream starts a new session.
+    pub session_gap_minutes: u32,
+    /// Whether the weekday × hour heatmap shows shares of the listening time (in percent) rather
+    /// than minutes.
+    pub normalize: bool,
+    /// The timezone the hours, days and months of the streams are computed in.
+    pub timezone: Timezone,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        QueryOptions {
+            content: ContentFilter::default(),
+            tracks: TrackGrouping::default(),
+            metric: Metric::default(),
+            session_gap_minutes: DEFAULT_SESSION_GAP_MINUTES,
+            normalize: false,
+            timezone: Timezone::default(),
+        }
+    }
+}
 pub fn get_total_listening_time_in_ms(data: &Vec<PlaybackRecord>) -> u64 {
     data.iter().map(|record| record.ms_played as u64).sum()
 }
-fn get_top_based_on_grouping<K, F>(data: &Vec<PlaybackRecord>, group_fn: F) -> Vec<(K, u64)>
+/// Returns the records that were played during `period` in `timezone`. Every other query can be
+/// narrowed down to a period by passing it the result of this function.
+pub fn get_records_in_period(
+    data: &Vec<PlaybackRecord>,
+    period: &Period,
+    timezone: Timezone,
+) -> Vec<PlaybackRecord> {
+    filter_by(data, |record| period.contains(&timezone.local(record)))
+}
+/// Returns the records whose content passes `filter`.
+pub fn get_records_of_content(
+    data: &Vec<PlaybackRecord>,
+    filter: ContentFilter,
+) -> Vec<PlaybackRecord> {
+    filter_by(data, |record| filter.contains(record.content_type))
+}
+/// Returns every calendar year of `timezone` with at least one stream, most recent first.
+pub fn get_years(data: &Vec<PlaybackRecord>, timezone: Timezone) -> Vec<i32> {
+    data.iter()
+        .map(|record| timezone.local(record).year())
+        .collect::<BTreeSet<i32>>()
+        .into_iter()
+        .rev()
+        .collect()
+}
+
+/// Returns the files the records were imported from with the number of records of each, sorted by
+/// file name. A record imported from several files is counted for each of them, the records whose
+/// source is not known are listed under an empty name.
+pub fn get_sources(data: &Vec<PlaybackRecord>) -> Vec<(String, usize)> {
+    data.iter()
+        .fold(BTreeMap::new(), |mut sources, record| {
+            if record.sources.is_empty() {
+                *sources.entry("").or_insert(0) += 1;
+            }
+            for source in &record.sources {
+                *sources.entry(source.as_str()).or_insert(0) += 1;
+            }
+            sources
+        })
+        .into_iter()
+        .map(|(source, count)| (source.to_string(), count))
+        .collect()
+}
+/// Returns the records of the extended streaming history, i.e. the ones whose fields such as
+/// `platform`, `conn_country` or `shuffle` are known.
+pub fn get_records_with_extended_fields(data: &Vec<PlaybackRecord>) -> Vec<PlaybackRecord> {
+    filter_by(data, |record| record.has_extended_fields())
+}
+/// Returns `true` if any record is a podcast episode.
+pub fn has_podcasts(data: &Vec<PlaybackRecord>) -> bool {
+    data.iter()
+        .any(|record| record.content_type == ContentType::Episode)
+}
+fn get_top_based_on_grouping<K, F>(
+    data: &Vec<PlaybackRecord>,
+    metric: Metric,
+    group_fn: F,
+) -> Vec<(K, u64)>
 where
     K: Eq + Hash + Clone + Default,
     F: Fn(&PlaybackRecord) -> K,
 {
     let mut grouped_data: Vec<(K, u64)> = group_by(data, group_fn)
         .iter()
-        .map(|(key, records)| (key.clone(), get_total_listening_time_in_ms(records)))
+        .map(|(key, records)| (key.clone(), metric.measure(records)))
         .filter(|(k, _)| k != &Default::default())
         .collect();
     grouped_data.sort_by(|(_, d1), (_, d2)| d2.cmp(d1));
     grouped_data
 }
-pub fn get_top_artists(data: &Vec<PlaybackRecord>) -> Vec<(String, u64)> {
-    get_top_based_on_grouping(data, |record| {
+pub fn get_top_artists(data: &Vec<PlaybackRecord>, metric: Metric) -> Vec<(String, u64)> {
+    get_top_based_on_grouping(data, metric, |record| {
         record.master_metadata_album_artist_name.clone()
     })
 }
+/// Turns a ranking into the share of `data` every entry accounts for, in percent.
+///
+/// # Arguments
+///
+/// * `data` - The records the ranking is computed over.
+/// * `cutoff` - The minimum share (in percent) of the entries that follow the first
+///              `minimum_elements` ones.
+/// * `minimum_elements` - The number of entries that are returned whatever their share.
+/// * `metric` - The metric `grouping_method` measures the entries with.
+/// * `grouping_method` - The query that ranks the entries, e.g. [`get_top_artists`].
 pub fn get_top_percentages<T>(
     data: &Vec<PlaybackRecord>,
     cutoff: f32,
     minimum_elements: usize,
+    metric: Metric,
     grouping_method: T,
 ) -> Vec<(String, f32)>
 where
     T: Fn(&Vec<PlaybackRecord>) -> Vec<(String, u64)>,
 {
-    let total_time = get_total_listening_time_in_ms(data);
+    let total_time = metric.measure(data);
 
     let top_artists = grouping_method(data);
     let total_t = total_time as f64;
@@ -48,7 +161,7 @@ where
         .map(|(n, r)| (n, (r * 10000.0).round() / 100.0))
         .collect();
     let mut ret = artists[..minimum_elements.clamp(0, artists.len())].to_vec();
-    let more = artists[minimum_elements.clamp(0, artists.len() - 1)..]
+    let more = artists[minimum_elements.clamp(0, artists.len())..]
         .to_vec()
         .into_iter()
         .filter(|(_, n)| n >= &cutoff)
@@ -60,77 +173,280 @@ pub fn get_top_artists_percentages(
     data: &Vec<PlaybackRecord>,
     cutoff: f32,
     minimum_elements: usize,
+    metric: Metric,
 ) -> Vec<(String, f32)> {
-    get_top_percentages(data, cutoff, minimum_elements, get_top_artists)
+    get_top_percentages(data, cutoff, minimum_elements, metric, |data| {
+        get_top_artists(data, metric)
+    })
 }
 pub fn get_top_songs_percentages(
     data: &Vec<PlaybackRecord>,
     cutoff: f32,
     minimum_elements: usize,
+    metric: Metric,
+    grouping: TrackGrouping,
 ) -> Vec<(String, f32)> {
-    get_top_percentages(data, cutoff, minimum_elements, get_top_songs)
+    get_top_percentages(data, cutoff, minimum_elements, metric, |data| {
+        get_top_song_names(data, metric, grouping)
+    })
 }
 pub fn get_top_albums_percentages(
     data: &Vec<PlaybackRecord>,
     cutoff: f32,
     minimum_elements: usize,
+    metric: Metric,
 ) -> Vec<(String, f32)> {
-    get_top_percentages(data, cutoff, minimum_elements, get_top_albums)
+    get_top_percentages(data, cutoff, minimum_elements, metric, |data| {
+        get_top_album_names(data, metric)
+    })
 }
 
-pub fn get_top_songs(data: &Vec<PlaybackRecord>) -> Vec<(String, u64)> {
-    get_top_based_on_grouping(data, |record| record.master_metadata_track_name.clone())
+/// Returns the songs measured with `metric`, most played first. Each song is described by the
+/// first record that played it.
+pub fn get_top_songs(
+    data: &Vec<PlaybackRecord>,
+    metric: Metric,
+    grouping: TrackGrouping,
+) -> Vec<(Track, u64)> {
+    let mut songs: Vec<(Track, u64)> = group_by(data, |record| grouping.key(record))
+        .into_iter()
+        .filter(|(key, _)| key.is_some())
+        .map(|(_, records)| (Track::from(&records[0]), metric.measure(&records)))
+        .collect();
+    songs.sort_by(|(_, d1), (_, d2)| d2.cmp(d1));
+    songs
+}
+/// Like [`get_top_songs`], but every song is named as "Title — Artist", see [`name_songs`].
+pub fn get_top_song_names(
+    data: &Vec<PlaybackRecord>,
+    metric: Metric,
+    grouping: TrackGrouping,
+) -> Vec<(String, u64)> {
+    let [songs] = name_songs([get_top_songs(data, metric, grouping)]);
+    songs
+}
+/// Names the songs of several rankings as "Title — Artist". With [`TrackGrouping::Uri`] two
+/// releases of a song can share their title and artist, those are named as
+/// "Title — Artist (Album)" in every ranking, so that a song keeps the same name from one ranking
+/// to the other.
+pub fn name_songs<const N: usize>(rankings: [Vec<(Track, u64)>; N]) -> [Vec<(String, u64)>; N] {
+    let mut albums: HashMap<String, HashSet<&str>> = HashMap::new();
+    for (track, _) in rankings.iter().flatten() {
+        albums
+            .entry(track.to_string())
+            .or_default()
+            .insert(&track.album);
+    }
+    let ambiguous: HashSet<String> = albums
+        .into_iter()
+        .filter(|(_, albums)| albums.len() > 1)
+        .map(|(name, _)| name)
+        .collect();
+    rankings.map(|ranking| {
+        ranking
+            .into_iter()
+            .map(|(track, value)| {
+                let name = track.to_string();
+                if ambiguous.contains(&name) && !track.album.is_empty() {
+                    (format!("{} ({})", name, track.album), value)
+                } else {
+                    (name, value)
+                }
+            })
+            .collect()
+    })
 }
 
-pub fn get_top_albums(data: &Vec<PlaybackRecord>) -> Vec<(String, u64)> {
-    get_top_based_on_grouping(data, |record| {
-        record.master_metadata_album_album_name.clone()
+/// Returns the albums measured with `metric`, most played first.
+pub fn get_top_albums(data: &Vec<PlaybackRecord>, metric: Metric) -> Vec<(Album, u64)> {
+    get_top_based_on_grouping(data, metric, Album::of)
+        .into_iter()
+        .filter_map(|(album, value)| Some((album?, value)))
+        .collect()
+}
+/// Like [`get_top_albums`], but every album is named as "Album — Artist".
+pub fn get_top_album_names(data: &Vec<PlaybackRecord>, metric: Metric) -> Vec<(String, u64)> {
+    get_top_albums(data, metric)
+        .into_iter()
+        .map(|(album, value)| (album.to_string(), value))
+        .collect()
+}
+pub fn get_top_shows(data: &Vec<PlaybackRecord>, metric: Metric) -> Vec<(String, u64)> {
+    get_top_based_on_grouping(data, metric, |record| record.podcast_show_name.clone())
+}
+pub fn get_top_episodes(data: &Vec<PlaybackRecord>, metric: Metric) -> Vec<(String, u64)> {
+    get_top_based_on_grouping(data, metric, |record| record.podcast_episode_name.clone())
+}
+/// Like [`get_top_artists_percentages`], but for the podcast shows. The percentages are shares of
+/// the podcasts listened to.
+pub fn get_top_shows_percentages(
+    data: &Vec<PlaybackRecord>,
+    cutoff: f32,
+    minimum_elements: usize,
+    metric: Metric,
+) -> Vec<(String, f32)> {
+    let podcasts = get_records_of_content(data, ContentFilter::Podcasts);
+    get_top_percentages(&podcasts, cutoff, minimum_elements, metric, |data| {
+        get_top_shows(data, metric)
+    })
+}
+/// Like [`get_top_shows_percentages`], but for the episodes.
+pub fn get_top_episodes_percentages(
+    data: &Vec<PlaybackRecord>,
+    cutoff: f32,
+    minimum_elements: usize,
+    metric: Metric,
+) -> Vec<(String, f32)> {
+    let podcasts = get_records_of_content(data, ContentFilter::Podcasts);
+    get_top_percentages(&podcasts, cutoff, minimum_elements, metric, |data| {
+        get_top_episodes(data, metric)
+    })
+}
+/// Returns the hours spent on podcasts in every month with at least one episode played, in
+/// chronological order, e.g. `("Oct 2023", 4.5)`.
+pub fn get_podcast_hours_per_month(
+    data: &Vec<PlaybackRecord>,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
+    let podcasts = get_records_of_content(data, ContentFilter::Podcasts);
+    let mut months: Vec<((i32, u32), u64)> = group_by(&podcasts, |record| {
+        let local = timezone.local(record);
+        (local.year(), local.month())
     })
+    .into_iter()
+    .map(|(month, records)| (month, get_total_listening_time_in_ms(&records)))
+    .collect();
+    months.sort_by_key(|(month, _)| *month);
+    months
+        .into_iter()
+        .map(|((year, month), ms)| {
+            let label = NaiveDate::from_ymd_opt(year, month, 1)
+                .map(|date| date.format("%b %Y").to_string())
+                .unwrap_or_default();
+            (label, ((ms as f64 / 360_000.0).round() / 10.0) as f32)
+        })
+        .collect()
 }
 pub fn get_top_countries(data: &Vec<PlaybackRecord>) -> Vec<(String, f32)> {
-    get_top_based_on_grouping(data, |record| record.conn_country.clone())
+    get_top_based_on_grouping(data, Metric::Time, |record| record.conn_country.clone())
         .into_par_iter()
         .map(|(s, u)| (s, (u as f32 / 60000.0).round()))
         .collect()
 }
 
 pub fn get_top_platforms(data: &Vec<PlaybackRecord>) -> Vec<(String, f32)> {
-    get_top_based_on_grouping(data, |record| record.platform.clone())
+    get_top_based_on_grouping(data, Metric::Time, |record| record.platform.clone())
         .into_par_iter()
         .map(|(s, u)| (s, (u as f32 / 60000.0).round()))
         .collect()
 }
-pub fn get_hours_of_the_day_distribution(data: &Vec<PlaybackRecord>) -> Vec<(String, f32)> {
+pub fn get_hours_of_the_day_distribution(
+    data: &Vec<PlaybackRecord>,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
     let total = get_total_listening_time_in_ms(data) as f32 / 60000.0;
-    get_top_based_on_grouping(data, |record| format!("{}", record.ts.time().hour() + 1))
-        .into_iter()
-        .map(|(h, ms)| (h, ms as f32 / 60000.0))
-        .map(|(h, m)| (h, m / total))
-        .map(|(h, p)| (h, (p * 100.0).round()))
-        .collect()
+    get_top_based_on_grouping(data, Metric::Time, |record| {
+        format!("{}", timezone.local(record).hour() + 1)
+    })
+    .into_iter()
+    .map(|(h, ms)| (h, ms as f32 / 60000.0))
+    .map(|(h, m)| (h, m / total))
+    .map(|(h, p)| (h, (p * 100.0).round()))
+    .collect()
 }
-pub fn get_day_distribution(data: &Vec<PlaybackRecord>) -> Vec<(String, f32)> {
-    let mut res: Vec<(chrono::Weekday, f32)> = group_by(data, |record| record.ts.weekday())
-        .into_par_iter()
-        .map(|(weekday, records)| {
-            (
-                weekday,
-                (get_total_listening_time_in_ms(&records) as f64 / 60000.0),
-            )
-        })
-        .map(|(weekday, r)| (weekday, r.round() as f32))
-        .collect();
+/// Returns the minutes listened in every hour of the day, ordered by hour.
+pub fn get_hours_of_the_day_minutes(
+    data: &Vec<PlaybackRecord>,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
+    let mut ret: Vec<(String, f32)> = get_top_based_on_grouping(data, Metric::Time, |record| {
+        format!("{}", timezone.local(record).hour() + 1)
+    })
+    .into_iter()
+    .map(|(h, ms)| (h, (ms as f64 / 60000.0).round() as f32))
+    .collect();
+    ret.sort_by_key(|(h, _)| h.parse::<u32>().unwrap_or(0));
+    ret
+}
+pub fn get_day_distribution(data: &Vec<PlaybackRecord>, timezone: Timezone) -> Vec<(String, f32)> {
+    let mut res: Vec<(chrono::Weekday, f32)> =
+        group_by(data, |record| timezone.local(record).weekday())
+            .into_par_iter()
+            .map(|(weekday, records)| {
+                (
+                    weekday,
+                    (get_total_listening_time_in_ms(&records) as f64 / 60000.0),
+                )
+            })
+            .map(|(weekday, r)| (weekday, r.round() as f32))
+            .collect();
     res.sort_by_key(|(weekday, _)| weekday.number_from_monday());
     res.into_iter()
         .map(|(weekday, r)| (weekday.to_string(), r))
         .collect()
 }
-pub fn get_top_days(data: &Vec<PlaybackRecord>, n: usize) -> Vec<(String, f32)> {
+/// Returns the listening time of every hour of every day of the week, as a matrix indexed by the
+/// day (from Monday) and the hour. The cells hold minutes, or shares of the total listening time
+/// in percent if `normalize` is set.
+pub fn get_weekday_hour_matrix(
+    data: &Vec<PlaybackRecord>,
+    normalize: bool,
+    timezone: Timezone,
+) -> [[f32; 24]; 7] {
+    let mut ms = [[0u64; 24]; 7];
+    for record in data {
+        let local = timezone.local(record);
+        let day = local.weekday().num_days_from_monday() as usize;
+        ms[day][local.hour() as usize] += record.ms_played as u64;
+    }
+    let total = get_total_listening_time_in_ms(data);
+    ms.map(|hours| {
+        hours.map(|ms| {
+            if !normalize {
+                (ms as f64 / 60000.0).round() as f32
+            } else if total == 0 {
+                0.0
+            } else {
+                ((ms as f64 / total as f64 * 1000.0).round() / 10.0) as f32
+            }
+        })
+    })
+}
+/// Flattens [`get_weekday_hour_matrix`] into one entry per cell labelled with the day and the hour
+/// (numbered from 1), e.g. `("Sat 24", 42.0)`, from `Mon 1` to `Sun 24`. Empty if there are
+/// no records.
+pub fn get_weekday_hour_distribution(
+    data: &Vec<PlaybackRecord>,
+    normalize: bool,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
+    if data.is_empty() {
+        return vec![];
+    }
+    get_weekday_hour_matrix(data, normalize, timezone)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(day, hours)| {
+            let weekday = Weekday::try_from(day as u8).unwrap_or(Weekday::Mon);
+            hours
+                .into_iter()
+                .enumerate()
+                .map(move |(hour, value)| (format!("{} {}", weekday, hour + 1), value))
+        })
+        .collect()
+}
+pub fn get_top_days(
+    data: &Vec<PlaybackRecord>,
+    n: usize,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
     sort_by_top_n(
-        &group_by(data, |record| record.ts.format("%d/%m/%y").to_string())
-            .into_par_iter()
-            .map(|(day, records)| (day, get_total_listening_time_in_ms(&records)))
-            .collect(),
+        &group_by(data, |record| {
+            timezone.local(record).format("%d/%m/%y").to_string()
+        })
+        .into_par_iter()
+        .map(|(day, records)| (day, get_total_listening_time_in_ms(&records)))
+        .collect(),
         n,
         |(_, r), (_, r2)| r.partial_cmp(r2).unwrap_or(std::cmp::Ordering::Equal),
     )
@@ -140,17 +456,22 @@ pub fn get_top_days(data: &Vec<PlaybackRecord>, n: usize) -> Vec<(String, f32)>
     .map(|(s, r)| (s, ((r / 1440.0) * 100.0).round()))
     .collect()
 }
-pub fn get_months_distribution(data: &Vec<PlaybackRecord>) -> Vec<(String, f32)> {
-    let mut ret: Vec<(String, f32)> = group_by(data, |record| record.ts.format("%B").to_string())
-        .into_iter()
-        .map(|(month, records)| {
-            (
-                month,
-                (get_total_listening_time_in_ms(&records) as f64 / 60000.0),
-            )
-        })
-        .map(|(weekday, r)| (weekday, r.round() as f32))
-        .collect();
+pub fn get_months_distribution(
+    data: &Vec<PlaybackRecord>,
+    timezone: Timezone,
+) -> Vec<(String, f32)> {
+    let mut ret: Vec<(String, f32)> = group_by(data, |record| {
+        timezone.local(record).format("%B").to_string()
+    })
+    .into_iter()
+    .map(|(month, records)| {
+        (
+            month,
+            (get_total_listening_time_in_ms(&records) as f64 / 60000.0),
+        )
+    })
+    .map(|(weekday, r)| (weekday, r.round() as f32))
+    .collect();
     ret.sort_by_key(|(month, _)| Month::from_str(month).unwrap().number_from_month());
     ret
 }
@@ -167,7 +488,8 @@ pub fn get_months_distribution(data: &Vec<PlaybackRecord>) -> Vec<(String, f32)>
 /// # Returns
 ///
 /// A `(a: u64, b: u64)` where a is the duration when field is `true` and b is the duration when
-/// field is `false` (in ms)
+/// field is `false` (in ms). Records for which the field is unknown (see
+/// [`PlaybackRecord::has_extended_fields`]) are ignored.
 pub fn playback_duration_difference_between_boolean_fields_in_ms<F>(
     data: &Vec<PlaybackRecord>,
     group_fn: F,
@@ -175,9 +497,12 @@ pub fn playback_duration_difference_between_boolean_fields_in_ms<F>(
 where
     F: Fn(&PlaybackRecord) -> bool,
 {
-    let mut grouped_data = group_by(data, group_fn)
-        .into_iter()
-        .map(|(key, records)| get_total_listening_time_in_ms(&records));
+    let mut grouped_data = group_by(
+        &filter_by(data, |record| record.has_extended_fields()),
+        group_fn,
+    )
+    .into_iter()
+    .map(|(key, records)| get_total_listening_time_in_ms(&records));
     (
         grouped_data.next().unwrap_or(0),
         grouped_data.next().unwrap_or(0),
@@ -201,63 +526,124 @@ pub fn playback_duration_difference_between_offline_or_not(
     playback_duration_difference_between_boolean_fields_in_ms(data, |record| record.offline)
 }
 
-/// Returns the most skipped songs that are at least half the average playback of a track
-pub fn get_most_skipped_songs(data: &Vec<PlaybackRecord>) -> Vec<(String, i64)> {
-    let dropoff = get_total_listening_time_in_ms(data) / data.len() as u64 / 2;
-    let mut skipped_rates: Vec<(String, i64)> =
-        group_by(data, |record| record.master_metadata_track_name.clone())
-            .into_iter()
-            .filter(|(_, records)| get_total_listening_time_in_ms(records) > dropoff)
-            .map(|(song, records)| {
-                (
-                    song,
-                    playback_duration_difference_between_boolean_fields_in_ms(&records, |r| {
-                        r.skipped
-                    }),
-                )
-            })
-            .map(|(song, (skipped, not_skipped))| {
-                (song, skipped as i64 / (skipped + not_skipped) as i64)
-            })
-            .collect();
-    skipped_rates.sort_by(|(_, r1), (_, r2)| r2.cmp(r1));
-    skipped_rates
-}
-/// Returns the most skipped artists that are at least half the average playback of a track
-pub fn get_most_skipped_artists(data: &Vec<PlaybackRecord>) -> Vec<(String, i64)> {
-    let dropoff = get_total_listening_time_in_ms(data) / data.len() as u64 / 2;
-    let mut skipped_rates: Vec<(String, i64)> = group_by(data, |record| {
-        record.master_metadata_album_artist_name.clone()
-    })
-    .into_iter()
-    .filter(|(_, records)| get_total_listening_time_in_ms(records) > dropoff)
-    .map(|(song, records)| {
-        (
-            song,
-            playback_duration_difference_between_boolean_fields_in_ms(&records, |r| r.skipped),
-        )
-    })
-    .map(|(song, (skipped, not_skipped))| (song, skipped as i64 / (skipped + not_skipped) as i64))
-    .collect();
-    skipped_rates.sort_by(|(_, r1), (_, r2)| r2.cmp(r1));
-    skipped_rates
-}
-/// Returns the most skipped albums that are at least half the average playback of a track
-pub fn get_most_skipped_albums(data: &Vec<PlaybackRecord>) -> Vec<(String, i64)> {
-    let dropoff = get_total_listening_time_in_ms(data) / data.len() as u64 / 2;
-    let mut skipped_rates: Vec<(String, i64)> = group_by(data, |record| {
-        record.master_metadata_album_album_name.clone()
-    })
-    .into_iter()
-    .filter(|(_, records)| get_total_listening_time_in_ms(records) > dropoff)
-    .map(|(song, records)| {
-        (
-            song,
-            playback_duration_difference_between_boolean_fields_in_ms(&records, |r| r.skipped),
-        )
-    })
-    .map(|(song, (skipped, not_skipped))| (song, skipped as i64 / (skipped + not_skipped) as i64))
-    .collect();
-    skipped_rates.sort_by(|(_, r1), (_, r2)| r2.cmp(r1));
-    skipped_rates
+#[cfg(test)]
+mod test_queries {
+    use super::*;
+    use crate::data_parser::test_records::record;
+    const json: &str = include_str!("./test/test_data.json");
+    const account_data_json: &str = r#"[
+        {"endTime":"2023-10-22 13:45","artistName":"Heidi Barabisch","trackName":"Ms","msPlayed":31545},
+        {"endTime":"2023-10-23 08:02","artistName":"Pearla Jodrelle","trackName":"Rev","msPlayed":25664}
+    ]"#;
+
+    #[test]
+    fn test_top_percentages_of_account_data() {
+        let records = PlaybackRecord::from_json(account_data_json.as_bytes()).unwrap();
+        // The account data export has no albums, and fewer artists than `minimum_elements`.
+        assert!(get_top_albums_percentages(&records, 4.0, 5, Metric::Time).is_empty());
+        assert_eq!(
+            get_top_artists_percentages(&records, 4.0, 5, Metric::Time).len(),
+            2
+        );
+        assert!(get_top_artists_percentages(&vec![], 4.0, 5, Metric::Time).is_empty());
+    }
+
+    #[test]
+    fn test_song_names_tell_releases_apart() {
+        let song = |album: &str, uri: &str| {
+            record()
+                .track("Song")
+                .artist("Artist")
+                .album(album)
+                .uri(uri)
+                .build()
+        };
+        let single = song("Single", "spotify:track:single");
+        let album = song("Album", "spotify:track:album");
+        let data = vec![single.clone(), single.clone(), album.clone()];
+
+        let names = get_top_song_names(&data, Metric::Streams, TrackGrouping::Uri);
+        assert_eq!(
+            names,
+            vec![
+                ("Song — Artist (Single)".to_string(), 2),
+                ("Song — Artist (Album)".to_string(), 1)
+            ]
+        );
+        let merged = get_top_song_names(&data, Metric::Streams, TrackGrouping::TitleAndArtist);
+        assert_eq!(merged, vec![("Song — Artist".to_string(), 3)]);
+
+        // The previous period only has the single, it keeps the name it has in the current one.
+        let [current, previous] = name_songs([
+            get_top_songs(&data, Metric::Streams, TrackGrouping::Uri),
+            get_top_songs(&vec![single], Metric::Streams, TrackGrouping::Uri),
+        ]);
+        assert_eq!(previous[0].0, current[0].0);
+    }
+
+    #[test]
+    fn test_albums_of_different_artists_with_the_same_name() {
+        let play = |artist: &str, title: &str| {
+            record()
+                .album("Greatest Hits")
+                .artist(artist)
+                .track(title)
+                .build()
+        };
+        let data = vec![
+            play("ABBA", "Waterloo"),
+            play("ABBA", "Dancing Queen"),
+            play("Queen", "Bohemian Rhapsody"),
+            play("Queen", "Bohemian Rhapsody"),
+            play("Queen", "Bohemian Rhapsody"),
+        ];
+        assert_eq!(
+            get_top_album_names(&data, Metric::Streams),
+            vec![
+                ("Greatest Hits — Queen".to_string(), 3),
+                ("Greatest Hits — ABBA".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_years() {
+        let data = vec![
+            record().at(2022, 6, 1, 12, 0).build(),
+            record().at(2023, 12, 31, 23, 30).build(),
+        ];
+        assert_eq!(get_years(&data, Timezone::Utc), vec![2023, 2022]);
+        // New Year's Eve is already over an hour east of UTC.
+        assert_eq!(get_years(&data, Timezone::Offset(60)), vec![2024, 2022]);
+    }
+
+    #[test]
+    fn test_weekday_hour_matrix() {
+        let mut records = PlaybackRecord::from_json(json.as_bytes()).unwrap();
+        records.truncate(2);
+        // Saturday 21 October 2023 at 23:10 UTC, and a Monday morning.
+        records[0].ts = Utc.with_ymd_and_hms(2023, 10, 21, 23, 10, 0).unwrap();
+        records[0].ms_played = 180_000;
+        records[1].ts = Utc.with_ymd_and_hms(2023, 10, 23, 8, 0, 0).unwrap();
+        records[1].ms_played = 60_000;
+
+        let matrix = get_weekday_hour_matrix(&records, false, Timezone::Utc);
+        assert_eq!(matrix[5][23], 3.0);
+        assert_eq!(matrix[0][8], 1.0);
+        assert_eq!(matrix.iter().flatten().sum::<f32>(), 4.0);
+        assert_eq!(
+            get_weekday_hour_matrix(&records, true, Timezone::Utc)[5][23],
+            75.0
+        );
+        // Saturday 23:10 UTC is Sunday 01:10 in UTC+2.
+        assert_eq!(
+            get_weekday_hour_matrix(&records, false, Timezone::Offset(120))[6][1],
+            3.0
+        );
+
+        let distribution = get_weekday_hour_distribution(&records, false, Timezone::Utc);
+        assert_eq!(distribution.len(), 7 * 24);
+        assert_eq!(distribution[5 * 24 + 23], ("Sat 24".to_string(), 3.0));
+        assert!(get_weekday_hour_distribution(&vec![], true, Timezone::Utc).is_empty());
+    }
 }
@@ -0,0 +1,287 @@
+use std::{collections::HashMap, fmt};
+
+use chrono::{Datelike, Months, NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    metric::Metric, playback_record::PlaybackRecord, processing::group_by,
+    queries::get_top_artists, timezone::Timezone,
+};
+
+/// Number of intervals a [`RankWindow::Rolling`] ranking is computed over.
+pub const ROLLING_INTERVALS: usize = 4;
+
+/// The length of the steps of a rank history.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RankInterval {
+    /// Weeks starting on Monday.
+    Week,
+    /// Calendar months.
+    #[default]
+    Month,
+}
+
+impl RankInterval {
+    pub const ALL: [Self; 2] = [Self::Week, Self::Month];
+
+    /// Returns the first day of the interval `date` lies in.
+    fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => date - TimeDelta::days(date.weekday().num_days_from_monday() as i64),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Returns the first day of the interval after the one starting on `start`.
+    fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => start + TimeDelta::days(7),
+            Self::Month => start + Months::new(1),
+        }
+    }
+
+    fn label(&self, start: NaiveDate) -> String {
+        match self {
+            Self::Week => start.format("%d/%m/%y").to_string(),
+            Self::Month => start.format("%b %Y").to_string(),
+        }
+    }
+}
+
+/// The streams every ranking of a rank history is computed over.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RankWindow {
+    /// Every stream from the start up to the end of the interval.
+    #[default]
+    Cumulative,
+    /// The streams of the interval and of the [`ROLLING_INTERVALS`] - 1 intervals before it.
+    Rolling,
+}
+
+impl RankWindow {
+    pub const ALL: [Self; 2] = [Self::Cumulative, Self::Rolling];
+}
+
+impl fmt::Display for RankInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Week => write!(f, "Weekly"),
+            Self::Month => write!(f, "Monthly"),
+        }
+    }
+}
+
+impl fmt::Display for RankWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Cumulative => write!(f, "Since the start of the period"),
+            Self::Rolling => write!(f, "Last {} intervals", ROLLING_INTERVALS),
+        }
+    }
+}
+
+/// The rank of an entry after every interval of a [`RankHistory`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RankSeries {
+    pub name: String,
+    /// The one-based rank after every interval, `None` where it is not ranked high enough to be
+    /// shown (or not ranked at all).
+    pub ranks: Vec<Option<usize>>,
+}
+
+/// How the ranking of the top entries evolved over time.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct RankHistory {
+    /// The labels of the intervals, in chronological order.
+    pub intervals: Vec<String>,
+    pub series: Vec<RankSeries>,
+}
+
+/// Ranks `totals` from the highest to the lowest value. Ties are broken by name so that the
+/// ranking is stable from one interval to the next.
+fn rank(totals: &HashMap<String, u64>) -> HashMap<&str, usize> {
+    let mut ranking: Vec<(&String, &u64)> = totals.iter().filter(|(_, v)| **v > 0).collect();
+    ranking.sort_by(|(n1, v1), (n2, v2)| v2.cmp(v1).then(n1.cmp(n2)));
+    ranking
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, _))| (name.as_str(), i + 1))
+        .collect()
+}
+
+/// Computes how the ranking of the top artists evolved over the period of `data`.
+///
+/// # Arguments
+///
+/// * `data` - The records, in any order.
+/// * `interval` - The length of the steps between two rankings.
+/// * `window` - The streams each ranking is computed over.
+/// * `metric` - What the artists are ranked by.
+/// * `timezone` - The timezone the weeks and months are computed in.
+/// * `top_n` - The number of artists followed, the top artists of the whole period.
+/// * `max_rank` - Ranks below it are left out of the series.
+///
+/// # Returns
+///
+/// A [`RankHistory`] with one ranking per interval from the first to the last stream, including
+/// the intervals without any stream.
+pub fn get_artist_rank_history(
+    data: &Vec<PlaybackRecord>,
+    interval: RankInterval,
+    window: RankWindow,
+    metric: Metric,
+    timezone: Timezone,
+    top_n: usize,
+    max_rank: usize,
+) -> RankHistory {
+    let per_interval: HashMap<NaiveDate, HashMap<String, u64>> = group_by(data, |record| {
+        interval.start_of(timezone.local(record).date_naive())
+    })
+    .into_iter()
+    .map(|(start, records)| {
+        let totals = get_top_artists(&records, metric).into_iter().collect();
+        (start, totals)
+    })
+    .collect();
+    let (Some(first), Some(last)) = (
+        per_interval.keys().min().copied(),
+        per_interval.keys().max().copied(),
+    ) else {
+        return RankHistory::default();
+    };
+    let mut starts = vec![first];
+    while let Some(&start) = starts.last().filter(|start| **start < last) {
+        starts.push(interval.next(start));
+    }
+
+    let followed: Vec<String> = get_top_artists(data, metric)
+        .into_iter()
+        .take(top_n)
+        .map(|(artist, _)| artist)
+        .collect();
+    let mut series: Vec<RankSeries> = followed
+        .iter()
+        .map(|name| RankSeries {
+            name: name.clone(),
+            ranks: Vec::with_capacity(starts.len()),
+        })
+        .collect();
+    let empty = HashMap::new();
+    let mut cumulative: HashMap<String, u64> = HashMap::new();
+    for (i, start) in starts.iter().enumerate() {
+        let totals = match window {
+            RankWindow::Cumulative => {
+                for (artist, value) in per_interval.get(start).unwrap_or(&empty) {
+                    *cumulative.entry(artist.clone()).or_insert(0) += value;
+                }
+                cumulative.clone()
+            }
+            RankWindow::Rolling => {
+                let mut rolling: HashMap<String, u64> = HashMap::new();
+                for start in &starts[(i + 1).saturating_sub(ROLLING_INTERVALS)..=i] {
+                    for (artist, value) in per_interval.get(start).unwrap_or(&empty) {
+                        *rolling.entry(artist.clone()).or_insert(0) += value;
+                    }
+                }
+                rolling
+            }
+        };
+        let ranks = rank(&totals);
+        for series in series.iter_mut() {
+            let rank = ranks
+                .get(series.name.as_str())
+                .copied()
+                .filter(|rank| *rank <= max_rank);
+            series.ranks.push(rank);
+        }
+    }
+
+    RankHistory {
+        intervals: starts
+            .into_iter()
+            .map(|start| interval.label(start))
+            .collect(),
+        series,
+    }
+}
+
+#[cfg(test)]
+mod test_rank_history {
+    use super::*;
+    use crate::data_parser::test_records::record;
+
+    /// One minute of `artist` on the given day of 2023.
+    fn play(artist: &str, month: u32, day: u32) -> PlaybackRecord {
+        record().at(2023, month, day, 12, 0).artist(artist).build()
+    }
+
+    fn ranks(history: &RankHistory, name: &str) -> Vec<Option<usize>> {
+        history
+            .series
+            .iter()
+            .find(|series| series.name == name)
+            .unwrap()
+            .ranks
+            .clone()
+    }
+
+    #[test]
+    fn test_cumulative_rank_history() {
+        // A leads in January, B overtakes it in March. Nothing is played in February.
+        let data = vec![
+            play("A", 1, 3),
+            play("A", 1, 4),
+            play("B", 1, 5),
+            play("B", 3, 1),
+            play("B", 3, 2),
+        ];
+        let history = get_artist_rank_history(
+            &data,
+            RankInterval::Month,
+            RankWindow::Cumulative,
+            Metric::Streams,
+            Timezone::Utc,
+            2,
+            10,
+        );
+        assert_eq!(history.intervals, vec!["Jan 2023", "Feb 2023", "Mar 2023"]);
+        assert_eq!(history.series[0].name, "B");
+        assert_eq!(ranks(&history, "A"), vec![Some(1), Some(1), Some(2)]);
+        assert_eq!(ranks(&history, "B"), vec![Some(2), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_rolling_rank_history() {
+        let data = vec![play("A", 1, 2), play("B", 1, 30)];
+        let history = get_artist_rank_history(
+            &data,
+            RankInterval::Week,
+            RankWindow::Rolling,
+            Metric::Time,
+            Timezone::Utc,
+            2,
+            1,
+        );
+        // Monday 2 January to Monday 30 January.
+        assert_eq!(history.intervals.len(), 5);
+        assert_eq!(history.intervals[0], "02/01/23");
+        // A drops out of the window of the fifth week, and B is never above the first rank.
+        assert_eq!(
+            ranks(&history, "A"),
+            vec![Some(1), Some(1), Some(1), Some(1), None]
+        );
+        assert_eq!(ranks(&history, "B"), vec![None, None, None, None, Some(1)]);
+        assert_eq!(
+            get_artist_rank_history(
+                &vec![],
+                RankInterval::Week,
+                RankWindow::Rolling,
+                Metric::Time,
+                Timezone::Utc,
+                2,
+                1
+            ),
+            RankHistory::default()
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{error::ParseError, playback_record::PlaybackRecord};
+
+/// The outcome of the import of a single file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileReport {
+    pub file: String,
+    pub imported: usize,
+    pub rejected: usize,
+    pub duplicates: usize,
+}
+
+/// Summary of an import, listing the records that were skipped in lenient mode.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImportReport {
+    /// Number of records that were imported.
+    pub imported: usize,
+    /// Every record that could not be read.
+    pub rejected: Vec<ParseError>,
+    /// Number of records that were dropped because they were already imported.
+    pub duplicates: usize,
+    /// The outcome of every file, in the order they were parsed.
+    pub files: Vec<FileReport>,
+}
+
+impl ImportReport {
+    /// Returns the number of records that were skipped.
+    pub fn skipped(&self) -> usize {
+        self.rejected.len()
+    }
+
+    /// Counts the rejected records per [`ParseError::reason`].
+    ///
+    /// # Returns
+    ///
+    /// A vector of `(reason, count)` tuples, most common reason first.
+    pub fn rejections_per_reason(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self
+            .rejected
+            .iter()
+            .fold(HashMap::new(), |mut counts, error| {
+                *counts.entry(error.reason()).or_insert(0) += 1;
+                counts
+            })
+            .into_iter()
+            .collect();
+        counts.sort_by(|(r1, c1), (r2, c2)| c2.cmp(c1).then_with(|| r1.cmp(r2)));
+        counts
+    }
+
+    /// Combines the reports of several files into one.
+    pub fn merge(mut self, other: ImportReport) -> Self {
+        self.imported += other.imported;
+        self.rejected.extend(other.rejected);
+        self.duplicates += other.duplicates;
+        self.files.extend(other.files);
+        self
+    }
+
+    /// Updates the counts after duplicates were dropped: the records of a file that are not part
+    /// of `kept` anymore are counted as duplicates of that file.
+    ///
+    /// # Arguments
+    ///
+    /// * `kept` - The records that are actually imported, with their `sources` set. A record is
+    ///            counted for the first of its sources.
+    pub fn with_kept<'a>(mut self, kept: impl IntoIterator<Item = &'a PlaybackRecord>) -> Self {
+        let per_file = kept.into_iter().fold(HashMap::new(), |mut counts, record| {
+            let source = record
+                .sources
+                .first()
+                .map(String::as_str)
+                .unwrap_or_default();
+            *counts.entry(source).or_insert(0) += 1;
+            counts
+        });
+        for file in self.files.iter_mut() {
+            let imported = per_file.get(file.file.as_str()).copied().unwrap_or(0);
+            file.duplicates += file.imported.saturating_sub(imported);
+            file.imported = imported;
+        }
+        self.imported = self.files.iter().map(|file| file.imported).sum();
+        self.duplicates = self.files.iter().map(|file| file.duplicates).sum();
+        self
+    }
+}
+
+/// Formats a number with a comma as thousands separator, e.g. `1482` as `1,482`.
+pub fn with_thousands_separator(number: usize) -> String {
+    let digits = number.to_string();
+    digits
+        .chars()
+        .enumerate()
+        .fold(String::new(), |mut formatted, (i, digit)| {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                formatted.push(',');
+            }
+            formatted.push(digit);
+            formatted
+        })
+}
+
+#[cfg(test)]
+mod test_report {
+    use super::*;
+    use crate::data_parser::{
+        history_file::HistoryFile, playback_record::PlaybackRecord, streaming::ParseMode,
+    };
+    const json: &str = include_str!("./test/test_data.json");
+
+    #[test]
+    fn test_with_thousands_separator() {
+        assert_eq!(with_thousands_separator(0), "0");
+        assert_eq!(with_thousands_separator(999), "999");
+        assert_eq!(with_thousands_separator(1482), "1,482");
+        assert_eq!(with_thousands_separator(182340), "182,340");
+        assert_eq!(with_thousands_separator(1000000), "1,000,000");
+    }
+
+    #[test]
+    fn test_lenient_import_report() {
+        let broken = json
+            .replacen("\"ms_played\":25664", "\"ms_played\":-1", 1)
+            .replacen("\"ms_played\":31545", "\"ms_played\":-1", 1)
+            .replacen("\"ms_played\":6691", "\"ms_played\":\"6691\"", 1);
+        let files = vec![
+            HistoryFile::new(
+                "Streaming_History_Audio_2023_0.json",
+                json.as_bytes().to_vec(),
+            ),
+            HistoryFile::new("Streaming_History_Audio_2023_1.json", broken.into_bytes()),
+        ];
+        assert!(PlaybackRecord::from_files(&files).is_err());
+
+        let (records, report) =
+            PlaybackRecord::from_files_with_mode(&files, ParseMode::Lenient).unwrap();
+        assert_eq!(report.imported, records.len());
+        assert_eq!(report.skipped(), 3);
+        assert!(report
+            .rejected
+            .iter()
+            .all(|e| e.file() == "Streaming_History_Audio_2023_1.json"));
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].rejected, 0);
+        assert_eq!(report.files[1].rejected, 3);
+        // The records the two files have in common are duplicates of the second one.
+        assert_eq!(report.files[0].imported, records.len());
+        assert_eq!(report.files[1].imported, 0);
+        assert_eq!(report.files[1].duplicates, report.duplicates);
+        let per_reason = report.rejections_per_reason();
+        assert_eq!(per_reason.len(), 2);
+        assert_eq!(per_reason[0].1, 2);
+        assert!(per_reason[0].0.starts_with("`ms_played`"));
+        assert_eq!(per_reason[1].1, 1);
+        assert!(per_reason[1].0.starts_with("`ms_played`"));
+    }
+}
@@ -0,0 +1,200 @@
+use chrono::{DateTime, TimeDelta, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{playback_record::PlaybackRecord, timezone::Timezone};
+
+/// Default break (in minutes) after which the next stream starts a new session.
+pub const DEFAULT_SESSION_GAP_MINUTES: u32 = 30;
+
+/// Upper bounds (in minutes) of the buckets of [`get_session_length_distribution`]. The last
+/// bucket holds every longer session.
+const SESSION_LENGTH_BUCKETS: [i64; 5] = [15, 30, 60, 120, 240];
+
+/// An uninterrupted stretch of listening.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// When the first stream of the session started.
+    pub start: DateTime<Utc>,
+    /// When the last stream of the session ended.
+    pub end: DateTime<Utc>,
+    /// Number of streams in the session.
+    pub tracks: usize,
+    /// The country the first stream of the session was played from.
+    pub country: String,
+}
+
+impl Session {
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+}
+
+/// Returns when the stream of `record` started. `ts` is the time the stream ended.
+fn started_at(record: &PlaybackRecord) -> DateTime<Utc> {
+    record.ts - TimeDelta::milliseconds(record.ms_played as i64)
+}
+
+/// Splits the streams into sessions.
+///
+/// # Arguments
+///
+/// * `data` - The records, in any order.
+/// * `gap_minutes` - A stream that starts more than `gap_minutes` after the previous one ended
+///                   starts a new session.
+///
+/// # Returns
+///
+/// The sessions in chronological order.
+pub fn get_sessions(data: &Vec<PlaybackRecord>, gap_minutes: u32) -> Vec<Session> {
+    let gap = TimeDelta::minutes(gap_minutes as i64);
+    let mut streams: Vec<(DateTime<Utc>, DateTime<Utc>, &str)> = data
+        .iter()
+        .map(|record| (started_at(record), record.ts, record.conn_country.as_str()))
+        .collect();
+    streams.sort();
+    let mut sessions: Vec<Session> = vec![];
+    for (start, end, country) in streams {
+        match sessions.last_mut() {
+            Some(session) if start - session.end <= gap => {
+                session.end = session.end.max(end);
+                session.tracks += 1;
+            }
+            _ => sessions.push(Session {
+                start,
+                end,
+                tracks: 1,
+                country: country.to_string(),
+            }),
+        }
+    }
+    sessions
+}
+
+/// Returns the number of sessions of every length bucket, e.g. `("30 min – 1 h", 12.0)`, from the
+/// shortest to the longest bucket. Empty if there are no sessions.
+pub fn get_session_length_distribution(sessions: &[Session]) -> Vec<(String, f32)> {
+    if sessions.is_empty() {
+        return vec![];
+    }
+    let format_minutes = |minutes: i64| {
+        if minutes < 60 {
+            format!("{} min", minutes)
+        } else {
+            format!("{} h", minutes / 60)
+        }
+    };
+    let mut counts = [0; SESSION_LENGTH_BUCKETS.len() + 1];
+    for session in sessions {
+        let minutes = session.duration().num_minutes();
+        let bucket = SESSION_LENGTH_BUCKETS
+            .iter()
+            .position(|bound| minutes < *bound)
+            .unwrap_or(SESSION_LENGTH_BUCKETS.len());
+        counts[bucket] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let label = match i {
+                0 => format!("< {}", format_minutes(SESSION_LENGTH_BUCKETS[0])),
+                i if i == SESSION_LENGTH_BUCKETS.len() => {
+                    format!("{}+", format_minutes(SESSION_LENGTH_BUCKETS[i - 1]))
+                }
+                i => format!(
+                    "{} – {}",
+                    format_minutes(SESSION_LENGTH_BUCKETS[i - 1]),
+                    format_minutes(SESSION_LENGTH_BUCKETS[i])
+                ),
+            };
+            (label, count as f32)
+        })
+        .collect()
+}
+
+/// Returns the longest session, the earliest one if several are as long.
+pub fn get_longest_session(sessions: &[Session]) -> Option<&Session> {
+    sessions
+        .iter()
+        .rev()
+        .max_by_key(|session| session.duration())
+}
+
+/// Returns the average number of streams per session, 0 if there are no sessions.
+pub fn get_average_tracks_per_session(sessions: &[Session]) -> f32 {
+    if sessions.is_empty() {
+        return 0.0;
+    }
+    let tracks: usize = sessions.iter().map(|session| session.tracks).sum();
+    tracks as f32 / sessions.len() as f32
+}
+
+/// Returns the number of sessions that started in every hour of the day of `timezone`, ordered
+/// by hour. Hours are numbered from 1 like in `get_hours_of_the_day_distribution`. Empty if there
+/// are no sessions.
+pub fn get_session_starts_by_hour(sessions: &[Session], timezone: Timezone) -> Vec<(String, f32)> {
+    if sessions.is_empty() {
+        return vec![];
+    }
+    let mut counts = [0; 24];
+    for session in sessions {
+        let start = timezone.at(session.start, &session.country);
+        counts[start.hour() as usize] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(hour, count)| (format!("{}", hour + 1), count as f32))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_sessions {
+    use super::*;
+    use crate::data_parser::test_records::record;
+    use chrono::TimeZone;
+
+    /// Records of three minutes that ended at the given minutes past 10:00 UTC.
+    fn streams_ending_at(minutes: &[u32]) -> Vec<PlaybackRecord> {
+        minutes
+            .iter()
+            .map(|minute| record().at(2023, 10, 22, 10, *minute).minutes(3).build())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_sessions() {
+        // The third stream starts 40 minutes after the second one ended.
+        let data = streams_ending_at(&[6, 3, 46, 49, 52]);
+        let sessions = get_sessions(&data, DEFAULT_SESSION_GAP_MINUTES);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].tracks, 2);
+        assert_eq!(
+            sessions[0].start,
+            Utc.with_ymd_and_hms(2023, 10, 22, 10, 0, 0).unwrap()
+        );
+        assert_eq!(sessions[0].duration(), TimeDelta::minutes(6));
+        assert_eq!(sessions[1].tracks, 3);
+
+        assert_eq!(get_sessions(&data, 60).len(), 1);
+        assert!(get_sessions(&vec![], 60).is_empty());
+    }
+
+    #[test]
+    fn test_session_statistics() {
+        let sessions = get_sessions(&streams_ending_at(&[3, 6, 46, 49, 52]), 30);
+        assert_eq!(get_longest_session(&sessions), Some(&sessions[1]));
+        assert_eq!(get_average_tracks_per_session(&sessions), 2.5);
+
+        let distribution = get_session_length_distribution(&sessions);
+        assert_eq!(distribution[0], ("< 15 min".to_string(), 2.0));
+        assert_eq!(distribution[5], ("4 h+".to_string(), 0.0));
+
+        let starts = get_session_starts_by_hour(&sessions, Timezone::Utc);
+        assert_eq!(starts.len(), 24);
+        assert_eq!(starts[10], ("11".to_string(), 2.0));
+        let starts = get_session_starts_by_hour(&sessions, Timezone::Offset(-60));
+        assert_eq!(starts[9], ("10".to_string(), 2.0));
+        assert!(get_session_starts_by_hour(&[], Timezone::Utc).is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashMap, hash::Hash};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    playback_record::PlaybackRecord,
+    processing::group_by,
+    track::{Album, Track, TrackGrouping, TrackKey},
+};
+
+/// Minimum number of plays for an entry to be ranked by its skip rate. Below it, a couple of
+/// skips would be enough to top the ranking.
+pub const MIN_PLAYS: usize = 5;
+
+/// Share of a track below which a stream that was stopped counts as skipped.
+pub const SKIP_COMPLETION: f32 = 0.5;
+
+/// How often an entry (track, artist, album, ...) was skipped.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SkipRate {
+    pub plays: usize,
+    pub skips: usize,
+}
+
+impl SkipRate {
+    /// Returns the share of the plays that were skipped, between 0 and 1.
+    pub fn rate(&self) -> f32 {
+        if self.plays == 0 {
+            return 0.0;
+        }
+        self.skips as f32 / self.plays as f32
+    }
+}
+
+/// The length of every track, estimated as its longest stream. Spotify does not export the
+/// length of the tracks, but a track that was played to the end once is known exactly.
+pub struct TrackLengths(HashMap<TrackKey, u32>);
+
+impl TrackLengths {
+    /// Estimates the lengths of the tracks of `history`, which should be the whole listening
+    /// history: within a single period a track may only ever have been skipped.
+    pub fn estimate(history: &Vec<PlaybackRecord>) -> Self {
+        let mut lengths = HashMap::new();
+        for record in history {
+            if let Some(key) = TrackGrouping::Uri.key(record) {
+                let length = lengths.entry(key).or_insert(0);
+                *length = record.ms_played.max(*length);
+            }
+        }
+        TrackLengths(lengths)
+    }
+
+    /// Returns the share of its track `record` played, 1 if the length of the track is not known.
+    pub fn completion(&self, record: &PlaybackRecord) -> f32 {
+        match TrackGrouping::Uri
+            .key(record)
+            .and_then(|key| self.0.get(&key))
+        {
+            Some(&length) if length > 0 => record.ms_played as f32 / length as f32,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Returns `true` if `record` was skipped.
+///
+/// The `skipped` flag is missing from older exports, so the reason the stream ended is used
+/// too: a track that played to its end (`trackdone`) is never a skip, one left with the forward
+/// button (`fwdbtn`) always is, and any other stream is a skip if it was stopped before
+/// [`SKIP_COMPLETION`] of the track.
+pub fn is_skip(record: &PlaybackRecord, lengths: &TrackLengths) -> bool {
+    match record.reason_end.as_str() {
+        "trackdone" => false,
+        "fwdbtn" => true,
+        _ => record.skipped || lengths.completion(record) < SKIP_COMPLETION,
+    }
+}
+
+/// Counts the plays and skips of every group of records, and ranks the groups with at least
+/// `min_plays` plays by skip rate, most skipped first. Records whose key is `None` and records
+/// without extended fields, which do not tell how the stream ended, are ignored.
+pub fn get_skip_rates<K, F>(
+    data: &Vec<PlaybackRecord>,
+    lengths: &TrackLengths,
+    min_plays: usize,
+    key_fn: F,
+) -> Vec<(K, SkipRate)>
+where
+    K: Eq + Hash,
+    F: Fn(&PlaybackRecord) -> Option<K>,
+{
+    let mut rates: Vec<(K, SkipRate)> = group_by(data, |record| {
+        key_fn(record).filter(|_| record.has_extended_fields())
+    })
+    .into_iter()
+    .filter_map(|(key, records)| {
+        let rate = SkipRate {
+            plays: records.len(),
+            skips: records
+                .iter()
+                .filter(|record| is_skip(record, lengths))
+                .count(),
+        };
+        Some((key?, rate))
+    })
+    .filter(|(_, rate)| rate.plays >= min_plays)
+    .collect();
+    rates.sort_by(|(_, r1), (_, r2)| {
+        r2.rate()
+            .total_cmp(&r1.rate())
+            .then(r2.plays.cmp(&r1.plays))
+    });
+    rates
+}
+
+/// Returns the tracks with at least `min_plays` plays, most skipped first.
+pub fn get_most_skipped_songs(
+    data: &Vec<PlaybackRecord>,
+    lengths: &TrackLengths,
+    min_plays: usize,
+) -> Vec<(Track, SkipRate)> {
+    let tracks: HashMap<TrackKey, Track> = data
+        .iter()
+        .rev()
+        .filter_map(|record| Some((TrackGrouping::Uri.key(record)?, Track::from(record))))
+        .collect();
+    get_skip_rates(data, lengths, min_plays, |record| {
+        TrackGrouping::Uri.key(record)
+    })
+    .into_iter()
+    .map(|(key, rate)| (tracks[&key].clone(), rate))
+    .collect()
+}
+
+/// Returns the artists with at least `min_plays` plays, most skipped first.
+pub fn get_most_skipped_artists(
+    data: &Vec<PlaybackRecord>,
+    lengths: &TrackLengths,
+    min_plays: usize,
+) -> Vec<(String, SkipRate)> {
+    get_skip_rates(data, lengths, min_plays, |record| {
+        Some(record.master_metadata_album_artist_name.clone()).filter(|artist| !artist.is_empty())
+    })
+}
+
+/// Returns the albums with at least `min_plays` plays, most skipped first.
+pub fn get_most_skipped_albums(
+    data: &Vec<PlaybackRecord>,
+    lengths: &TrackLengths,
+    min_plays: usize,
+) -> Vec<(Album, SkipRate)> {
+    get_skip_rates(data, lengths, min_plays, Album::of)
+}
+
+#[cfg(test)]
+mod test_skips {
+    use super::*;
+    use crate::data_parser::test_records::record;
+    const json: &str = include_str!("./test/test_data.json");
+
+    fn plays(reasons: &[(&str, u32, bool)]) -> Vec<PlaybackRecord> {
+        reasons
+            .iter()
+            .map(|(reason_end, ms_played, skipped)| {
+                record()
+                    .reason_end(reason_end)
+                    .ms_played(*ms_played)
+                    .skipped(*skipped)
+                    .build()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_is_skip() {
+        let data = plays(&[
+            ("trackdone", 200_000, true),
+            ("fwdbtn", 190_000, false),
+            ("endplay", 20_000, false),
+            ("endplay", 180_000, false),
+            ("logout", 180_000, true),
+        ]);
+        let lengths = TrackLengths::estimate(&data);
+        let skips: Vec<bool> = data
+            .iter()
+            .map(|record| is_skip(record, &lengths))
+            .collect();
+        assert_eq!(skips, vec![false, true, true, false, true]);
+    }
+
+    #[test]
+    fn test_skip_rates_are_fractional() {
+        let data = plays(&[
+            ("trackdone", 200_000, false),
+            ("trackdone", 200_000, false),
+            ("fwdbtn", 10_000, true),
+            ("trackdone", 200_000, false),
+            ("fwdbtn", 10_000, true),
+        ]);
+        let lengths = TrackLengths::estimate(&data);
+        let rates = get_most_skipped_songs(&data, &lengths, MIN_PLAYS);
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].1, SkipRate { plays: 5, skips: 2 });
+        assert_eq!(rates[0].1.rate(), 0.4);
+        assert_eq!(rates[0].0, Track::from(&data[0]));
+
+        assert!(get_most_skipped_artists(&data, &lengths, MIN_PLAYS + 1).is_empty());
+        assert_eq!(
+            get_most_skipped_albums(&data, &lengths, MIN_PLAYS)[0]
+                .1
+                .skips,
+            2
+        );
+    }
+
+    #[test]
+    fn test_lengths_come_from_the_whole_history() {
+        let history = plays(&[
+            ("trackdone", 200_000, false),
+            ("endplay", 60_000, false),
+            ("endplay", 60_000, false),
+        ]);
+        // Within the period alone, the track looks a minute long and never skipped.
+        let period = history[1..].to_vec();
+        let rates = get_most_skipped_songs(&period, &TrackLengths::estimate(&period), 1);
+        assert_eq!(rates[0].1.skips, 0);
+        let rates = get_most_skipped_songs(&period, &TrackLengths::estimate(&history), 1);
+        assert_eq!(rates[0].1, SkipRate { plays: 2, skips: 2 });
+    }
+
+    #[test]
+    fn test_albums_with_the_same_name_are_told_apart() {
+        let play = |artist: &str, title: &str| {
+            record()
+                .album("Greatest Hits")
+                .artist(artist)
+                .track(title)
+                .reason_end("trackdone")
+                .build()
+        };
+        let data = vec![
+            play("ABBA", "Waterloo"),
+            play("ABBA", "Dancing Queen"),
+            play("Queen", "Bohemian Rhapsody"),
+        ];
+        let lengths = TrackLengths::estimate(&data);
+        let mut albums: Vec<(String, usize)> = get_most_skipped_albums(&data, &lengths, 1)
+            .into_iter()
+            .map(|(album, rate)| (album.to_string(), rate.plays))
+            .collect();
+        albums.sort();
+        assert_eq!(
+            albums,
+            vec![
+                ("Greatest Hits — ABBA".to_string(), 2),
+                ("Greatest Hits — Queen".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_rates_are_sorted() {
+        let data = PlaybackRecord::from_json(json.as_bytes()).unwrap();
+        let rates = get_most_skipped_artists(&data, &TrackLengths::estimate(&data), 1);
+        assert!(rates.windows(2).all(|w| w[0].1.rate() >= w[1].1.rate()));
+        assert!(rates
+            .iter()
+            .all(|(_, rate)| rate.skips <= rate.plays && rate.plays >= 1));
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{playback_record::PlaybackRecord, timezone::Timezone};
+
+/// A run of consecutive days with some listening.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    /// The first day of the streak.
+    pub start: NaiveDate,
+    /// The last day of the streak, included.
+    pub end: NaiveDate,
+}
+
+impl Streak {
+    /// Returns the number of days of the streak.
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+}
+
+/// The streaks of a set of records, see [`get_streak_stats`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct StreakStats {
+    pub longest: Option<Streak>,
+    /// The streak that includes the last day of the whole history, i.e. the one that was still
+    /// running when the history was exported, whatever the period.
+    pub current: Option<Streak>,
+    /// The artist played on the most consecutive days, with that streak.
+    pub longest_artist: Option<(String, Streak)>,
+    /// Number of days without any listening between the first and the last day of the records.
+    pub days_without_listening: i64,
+    /// The longest streak of the artist that was asked for, `None` if it was never played.
+    pub artist: Option<Streak>,
+}
+
+/// Returns the days of `timezone` with at least one stream.
+pub fn get_listening_days(data: &Vec<PlaybackRecord>, timezone: Timezone) -> BTreeSet<NaiveDate> {
+    data.iter()
+        .map(|record| timezone.local(record).date_naive())
+        .collect()
+}
+
+/// Splits days into runs of consecutive days, in chronological order.
+pub fn get_streaks(days: &BTreeSet<NaiveDate>) -> Vec<Streak> {
+    let mut streaks: Vec<Streak> = vec![];
+    for &day in days {
+        match streaks.last_mut() {
+            Some(streak) if streak.end.succ_opt() == Some(day) => streak.end = day,
+            _ => streaks.push(Streak {
+                start: day,
+                end: day,
+            }),
+        }
+    }
+    streaks
+}
+
+/// Returns the longest of `streaks`, the earliest one if several are as long.
+fn longest(streaks: &[Streak]) -> Option<Streak> {
+    streaks
+        .iter()
+        .rev()
+        .max_by_key(|streak| streak.days())
+        .copied()
+}
+
+/// Returns the longest streak of days on which `artist` was played.
+pub fn get_artist_streak(
+    data: &Vec<PlaybackRecord>,
+    artist: &str,
+    timezone: Timezone,
+) -> Option<Streak> {
+    let days = data
+        .iter()
+        .filter(|record| record.master_metadata_album_artist_name == artist)
+        .map(|record| timezone.local(record).date_naive())
+        .collect();
+    longest(&get_streaks(&days))
+}
+
+/// Returns the artist played on the most consecutive days, with that streak.
+pub fn get_longest_artist_streak(
+    data: &Vec<PlaybackRecord>,
+    timezone: Timezone,
+) -> Option<(String, Streak)> {
+    let mut days: HashMap<&str, BTreeSet<NaiveDate>> = HashMap::new();
+    for record in data {
+        if !record.master_metadata_album_artist_name.is_empty() {
+            days.entry(&record.master_metadata_album_artist_name)
+                .or_default()
+                .insert(timezone.local(record).date_naive());
+        }
+    }
+    days.into_iter()
+        .filter_map(|(artist, days)| Some((artist.to_string(), longest(&get_streaks(&days))?)))
+        .max_by(|(a1, s1), (a2, s2)| {
+            s1.days()
+                .cmp(&s2.days())
+                .then(s2.start.cmp(&s1.start))
+                .then(a2.cmp(a1))
+        })
+}
+
+/// Computes the streaks of `data`.
+///
+/// # Arguments
+///
+/// * `history` - Every record, the current streak is computed over it so that it does not end
+///               with the selected period.
+/// * `data` - The records of the period, in any order.
+/// * `artist` - An artist to also compute the longest streak of, e.g. to answer "how many days in
+///              a row did I play X".
+/// * `timezone` - The timezone the days are computed in.
+pub fn get_streak_stats(
+    history: &Vec<PlaybackRecord>,
+    data: &Vec<PlaybackRecord>,
+    artist: Option<&str>,
+    timezone: Timezone,
+) -> StreakStats {
+    let days = get_listening_days(data, timezone);
+    let streaks = get_streaks(&days);
+    let days_without_listening = match (days.first(), days.last()) {
+        (Some(first), Some(last)) => (*last - *first).num_days() + 1 - days.len() as i64,
+        _ => 0,
+    };
+    StreakStats {
+        longest: longest(&streaks),
+        current: get_streaks(&get_listening_days(history, timezone))
+            .last()
+            .copied(),
+        longest_artist: get_longest_artist_streak(data, timezone),
+        days_without_listening,
+        artist: artist.and_then(|artist| get_artist_streak(data, artist, timezone)),
+    }
+}
+
+#[cfg(test)]
+mod test_streaks {
+    use super::*;
+    use crate::data_parser::test_records::record;
+
+    /// Records of `artist` played on the given days of October 2023.
+    fn plays(artist: &str, days: &[u32]) -> Vec<PlaybackRecord> {
+        days.iter()
+            .map(|day| record().at(2023, 10, *day, 12, 0).artist(artist).build())
+            .collect()
+    }
+
+    fn october(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_get_streaks() {
+        let days = get_listening_days(&plays("A", &[1, 2, 2, 3, 5, 7, 8]), Timezone::Utc);
+        assert_eq!(
+            get_streaks(&days),
+            vec![
+                Streak {
+                    start: october(1),
+                    end: october(3)
+                },
+                Streak {
+                    start: october(5),
+                    end: october(5)
+                },
+                Streak {
+                    start: october(7),
+                    end: october(8)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_streak_stats() {
+        let mut data = plays("A", &[1, 2, 3, 10, 11]);
+        data.extend(plays("B", &[3, 4, 5, 6, 10]));
+        let stats = get_streak_stats(&data, &data, Some("A"), Timezone::Utc);
+        assert_eq!(
+            stats.longest,
+            Some(Streak {
+                start: october(1),
+                end: october(6)
+            })
+        );
+        assert_eq!(stats.current.map(|streak| streak.days()), Some(2));
+        assert_eq!(
+            stats
+                .longest_artist
+                .map(|(artist, streak)| (artist, streak.days())),
+            Some(("B".to_string(), 4))
+        );
+        assert_eq!(stats.days_without_listening, 3);
+        assert_eq!(stats.artist.map(|streak| streak.days()), Some(3));
+        assert_eq!(
+            get_streak_stats(&data, &data, Some("C"), Timezone::Utc).artist,
+            None
+        );
+        assert_eq!(
+            get_streak_stats(&vec![], &vec![], None, Timezone::Utc),
+            StreakStats::default()
+        );
+        // The plays at noon UTC are on the same days 11 hours away.
+        assert_eq!(
+            get_streak_stats(&data, &data, Some("A"), Timezone::Offset(-11 * 60)).longest,
+            stats.longest
+        );
+    }
+
+    #[test]
+    fn test_current_streak_is_computed_over_the_history() {
+        let september = record().at(2023, 9, 30, 12, 0).artist("A").build();
+        let mut history = vec![september.clone()];
+        history.extend(plays("A", &[1, 2]));
+        let stats = get_streak_stats(&history, &vec![september], None, Timezone::Utc);
+        assert_eq!(
+            stats.current,
+            Some(Streak {
+                start: NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+                end: october(2)
+            })
+        );
+        assert_eq!(stats.longest.map(|streak| streak.days()), Some(1));
+    }
+}
@@ -0,0 +1,256 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{DeserializeOwned, SeqAccess, Visitor};
+use serde::Deserializer as _;
+use serde_json::{Deserializer, Value};
+use serde_path_to_error::Track;
+
+use super::{error::ParseError, playback_record::PlaybackRecord};
+
+/// Number of records parsed between two progress reports.
+pub const PROGRESS_INTERVAL: usize = 1_000;
+
+/// How records that cannot be read are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// The first record that cannot be read fails the whole file.
+    #[default]
+    Strict,
+    /// Records that cannot be read are skipped and reported. Errors that leave the rest of the
+    /// file unreadable, such as truncated JSON, still fail the whole file.
+    Lenient,
+}
+
+/// Visitor that walks the top-level array of a streaming history file one element at a time.
+///
+/// Every element is deserialized as `R` and immediately converted into a sanitized
+/// [`PlaybackRecord`], so the raw representation of a record never outlives its own iteration.
+/// In [`ParseMode::Lenient`] an element is first read as a `serde_json::Value`, so that a record
+/// with invalid fields can be skipped without losing track of the array.
+struct PlaybackRecordVisitor<R, F> {
+    mode: ParseMode,
+    on_progress: F,
+    raw: PhantomData<R>,
+}
+
+impl<'de, R, F> Visitor<'de> for PlaybackRecordVisitor<R, F>
+where
+    R: DeserializeOwned + Into<PlaybackRecord>,
+    F: FnMut(usize),
+{
+    type Value = (Vec<PlaybackRecord>, Vec<ParseError>);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of playback records")
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut records = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        let mut rejected = vec![];
+        for index in 0.. {
+            let raw = match self.mode {
+                ParseMode::Strict => match seq.next_element::<R>()? {
+                    Some(raw) => Ok(raw),
+                    None => break,
+                },
+                ParseMode::Lenient => match seq.next_element::<Value>()? {
+                    Some(value) => serde_path_to_error::deserialize::<_, R>(value)
+                        .map_err(|e| ParseError::from_record_serde(index, e.inner(), e.path())),
+                    None => break,
+                },
+            };
+            match raw {
+                Ok(raw) => records.push(raw.into()),
+                Err(e) => rejected.push(e),
+            }
+            if (index + 1) % PROGRESS_INTERVAL == 0 {
+                (self.on_progress)(index + 1);
+            }
+        }
+        (self.on_progress)(records.len() + rejected.len());
+        Ok((records, rejected))
+    }
+}
+
+/// Parses a streaming history file without materializing it as a `serde_json::Value` first.
+///
+/// # Arguments
+///
+/// * `json` - The raw bytes of a streaming history file (a top-level JSON array).
+/// * `mode` - Whether records that cannot be read fail the file or are skipped.
+/// * `on_progress` - A closure that is called with the number of records parsed so far. It is
+///                   called every [`PROGRESS_INTERVAL`] records and once more when the file is done.
+///
+/// # Returns
+///
+/// The sanitized records in the order they appear in the file together with the errors of the
+/// skipped records (always empty in [`ParseMode::Strict`]), or a [`ParseError`] describing why
+/// the file could not be read. The file names of the errors are left empty.
+pub fn parse_records<R, F>(
+    json: &[u8],
+    mode: ParseMode,
+    on_progress: F,
+) -> Result<(Vec<PlaybackRecord>, Vec<ParseError>), ParseError>
+where
+    R: DeserializeOwned + Into<PlaybackRecord>,
+    F: FnMut(usize),
+{
+    let mut deserializer = Deserializer::from_slice(json);
+    let mut track = Track::new();
+    let records = serde_path_to_error::Deserializer::new(&mut deserializer, &mut track)
+        .deserialize_seq(PlaybackRecordVisitor::<R, F> {
+            mode,
+            on_progress,
+            raw: PhantomData,
+        });
+    match records {
+        Ok(records) => {
+            deserializer
+                .end()
+                .map_err(|e| ParseError::from_serde(&e, &track.path()))?;
+            Ok(records)
+        }
+        Err(e) => Err(ParseError::from_serde(&e, &track.path())),
+    }
+}
+
+/// Counts the records of a streaming history file without parsing them, by counting the objects
+/// directly inside the top-level array. Used to estimate how long parsing the file will take.
+pub fn count_records(json: &[u8]) -> usize {
+    let mut count = 0;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in json {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                if byte == b'{' && depth == 1 {
+                    count += 1;
+                }
+                depth += 1;
+            }
+            b']' | b'}' => depth -= 1,
+            _ => (),
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod test_streaming {
+    use super::*;
+    use crate::data_parser::playback_record::UnsanitizedPlaybackRecord;
+    const json: &str = include_str!("./test/test_data.json");
+
+    #[test]
+    fn test_parse_records_matches_eager_parsing() {
+        let eager: Vec<PlaybackRecord> =
+            serde_json::from_str::<Vec<UnsanitizedPlaybackRecord>>(json)
+                .unwrap()
+                .into_iter()
+                .map(PlaybackRecord::from)
+                .collect();
+        let (streamed, _) = parse_records::<UnsanitizedPlaybackRecord, _>(
+            json.as_bytes(),
+            ParseMode::Strict,
+            |_| (),
+        )
+        .unwrap();
+        assert_eq!(eager, streamed);
+    }
+
+    #[test]
+    fn test_parse_records_reports_progress() {
+        let mut reports = vec![];
+        let (records, _) = parse_records::<UnsanitizedPlaybackRecord, _>(
+            json.as_bytes(),
+            ParseMode::Strict,
+            |n| reports.push(n),
+        )
+        .unwrap();
+        assert_eq!(reports.last(), Some(&records.len()));
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_parse_records_rejects_non_array() {
+        assert!(
+            parse_records::<UnsanitizedPlaybackRecord, _>(b"{}", ParseMode::Strict, |_| ())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_records_rejects_trailing_data() {
+        assert!(
+            parse_records::<UnsanitizedPlaybackRecord, _>(b"[] []", ParseMode::Strict, |_| ())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_records_lenient_skips_bad_records() {
+        let broken = json.replacen("\"ts\":\"2023-10-22T00:00:00Z\"", "\"ts\":\"yesterday\"", 1);
+        assert!(parse_records::<UnsanitizedPlaybackRecord, _>(
+            broken.as_bytes(),
+            ParseMode::Strict,
+            |_| ()
+        )
+        .is_err());
+
+        let (records, rejected) = parse_records::<UnsanitizedPlaybackRecord, _>(
+            broken.as_bytes(),
+            ParseMode::Lenient,
+            |_| (),
+        )
+        .unwrap();
+        let (all_records, _) = parse_records::<UnsanitizedPlaybackRecord, _>(
+            json.as_bytes(),
+            ParseMode::Lenient,
+            |_| (),
+        )
+        .unwrap();
+        assert_eq!(records[..], all_records[1..]);
+        assert!(matches!(
+            &rejected[..],
+            [ParseError::InvalidRecord { index: 0, field: Some(field), .. }] if field == "ts"
+        ));
+    }
+
+    #[test]
+    fn test_parse_records_lenient_fails_on_truncated_file() {
+        let truncated = &json[..json.len() / 2];
+        assert!(parse_records::<UnsanitizedPlaybackRecord, _>(
+            truncated.as_bytes(),
+            ParseMode::Lenient,
+            |_| ()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_count_records() {
+        let (records, _) = parse_records::<UnsanitizedPlaybackRecord, _>(
+            json.as_bytes(),
+            ParseMode::Strict,
+            |_| (),
+        )
+        .unwrap();
+        assert_eq!(count_records(json.as_bytes()), records.len());
+        assert_eq!(count_records(br#"[{"a":"}{\"{"},{"b":[{}]}]"#), 2);
+        assert_eq!(count_records(b"[]"), 0);
+    }
+}
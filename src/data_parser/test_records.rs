@@ -0,0 +1,86 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+use super::playback_record::PlaybackRecord;
+
+const json: &str = include_str!("./test/test_data.json");
+
+/// Builds the records of the tests, so that each test only spells out the fields it is about.
+pub(crate) struct RecordBuilder(PlaybackRecord);
+
+/// Starts a record from the first record of `test_data.json`, played for one minute and ended on
+/// Sunday 1 October 2023 at 12:00 UTC.
+pub(crate) fn record() -> RecordBuilder {
+    let record = PlaybackRecord::from_json(json.as_bytes())
+        .unwrap()
+        .remove(0);
+    RecordBuilder(PlaybackRecord {
+        ts: Utc.with_ymd_and_hms(2023, 10, 1, 12, 0, 0).unwrap(),
+        ms_played: 60_000,
+        ..record
+    })
+}
+
+impl RecordBuilder {
+    /// Sets when the stream ended, in UTC.
+    pub fn at(self, year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Self {
+        self.ended_at(
+            Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+                .unwrap(),
+        )
+    }
+
+    pub fn ended_at(mut self, ts: DateTime<Utc>) -> Self {
+        self.0.ts = ts;
+        self
+    }
+
+    /// Sets the title of the track, and a URI made of it.
+    pub fn track(mut self, title: &str) -> Self {
+        self.0.master_metadata_track_name = title.to_string();
+        self.0.spotify_track_uri = format!("spotify:track:{}", title);
+        self
+    }
+
+    pub fn uri(mut self, uri: &str) -> Self {
+        self.0.spotify_track_uri = uri.to_string();
+        self
+    }
+
+    pub fn artist(mut self, artist: &str) -> Self {
+        self.0.master_metadata_album_artist_name = artist.to_string();
+        self
+    }
+
+    pub fn album(mut self, album: &str) -> Self {
+        self.0.master_metadata_album_album_name = album.to_string();
+        self
+    }
+
+    pub fn ms_played(mut self, ms_played: u32) -> Self {
+        self.0.ms_played = ms_played;
+        self
+    }
+
+    pub fn minutes(self, minutes: u32) -> Self {
+        self.ms_played(minutes * 60_000)
+    }
+
+    pub fn country(mut self, country: &str) -> Self {
+        self.0.conn_country = country.to_string();
+        self
+    }
+
+    pub fn reason_end(mut self, reason_end: &str) -> Self {
+        self.0.reason_end = reason_end.to_string();
+        self
+    }
+
+    pub fn skipped(mut self, skipped: bool) -> Self {
+        self.0.skipped = skipped;
+        self
+    }
+
+    pub fn build(self) -> PlaybackRecord {
+        self.0
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use super::playback_record::PlaybackRecord;
+
+/// Returns the timezone of a country, `None` if the country is not known or spans several
+/// timezones (e.g. the US, Canada, Mexico, Brazil, Russia or Australia), since the stream could
+/// have been played in any of them.
+fn country_timezone(country: &str) -> Option<Tz> {
+    let timezone = match country {
+        "PE" => Tz::America__Lima,
+        "CO" => Tz::America__Bogota,
+        "EC" => Tz::America__Guayaquil,
+        "PA" => Tz::America__Panama,
+        "JM" => Tz::America__Jamaica,
+        "CR" => Tz::America__Costa_Rica,
+        "GT" => Tz::America__Guatemala,
+        "HN" => Tz::America__Tegucigalpa,
+        "NI" => Tz::America__Managua,
+        "SV" => Tz::America__El_Salvador,
+        "VE" => Tz::America__Caracas,
+        "BO" => Tz::America__La_Paz,
+        "DO" => Tz::America__Santo_Domingo,
+        "PR" => Tz::America__Puerto_Rico,
+        "AR" => Tz::America__Argentina__Buenos_Aires,
+        "CL" => Tz::America__Santiago,
+        "UY" => Tz::America__Montevideo,
+        "PY" => Tz::America__Asuncion,
+        "GB" => Tz::Europe__London,
+        "IE" => Tz::Europe__Dublin,
+        "PT" => Tz::Europe__Lisbon,
+        "IS" => Tz::Atlantic__Reykjavik,
+        "GH" => Tz::Africa__Accra,
+        "SN" => Tz::Africa__Dakar,
+        "CI" => Tz::Africa__Abidjan,
+        "FR" => Tz::Europe__Paris,
+        "DE" => Tz::Europe__Berlin,
+        "ES" => Tz::Europe__Madrid,
+        "IT" => Tz::Europe__Rome,
+        "NL" => Tz::Europe__Amsterdam,
+        "BE" => Tz::Europe__Brussels,
+        "LU" => Tz::Europe__Luxembourg,
+        "CH" => Tz::Europe__Zurich,
+        "AT" => Tz::Europe__Vienna,
+        "DK" => Tz::Europe__Copenhagen,
+        "SE" => Tz::Europe__Stockholm,
+        "NO" => Tz::Europe__Oslo,
+        "PL" => Tz::Europe__Warsaw,
+        "CZ" => Tz::Europe__Prague,
+        "SK" => Tz::Europe__Bratislava,
+        "HU" => Tz::Europe__Budapest,
+        "SI" => Tz::Europe__Ljubljana,
+        "HR" => Tz::Europe__Zagreb,
+        "BA" => Tz::Europe__Sarajevo,
+        "RS" => Tz::Europe__Belgrade,
+        "ME" => Tz::Europe__Podgorica,
+        "MK" => Tz::Europe__Skopje,
+        "AL" => Tz::Europe__Tirane,
+        "MT" => Tz::Europe__Malta,
+        "AD" => Tz::Europe__Andorra,
+        "MC" => Tz::Europe__Monaco,
+        "LI" => Tz::Europe__Vaduz,
+        "NG" => Tz::Africa__Lagos,
+        "MA" => Tz::Africa__Casablanca,
+        "DZ" => Tz::Africa__Algiers,
+        "TN" => Tz::Africa__Tunis,
+        "FI" => Tz::Europe__Helsinki,
+        "EE" => Tz::Europe__Tallinn,
+        "LV" => Tz::Europe__Riga,
+        "LT" => Tz::Europe__Vilnius,
+        "GR" => Tz::Europe__Athens,
+        "CY" => Tz::Asia__Nicosia,
+        "RO" => Tz::Europe__Bucharest,
+        "BG" => Tz::Europe__Sofia,
+        "UA" => Tz::Europe__Kyiv,
+        "MD" => Tz::Europe__Chisinau,
+        "IL" => Tz::Asia__Jerusalem,
+        "PS" => Tz::Asia__Hebron,
+        "EG" => Tz::Africa__Cairo,
+        "ZA" => Tz::Africa__Johannesburg,
+        "LB" => Tz::Asia__Beirut,
+        "JO" => Tz::Asia__Amman,
+        "TR" => Tz::Europe__Istanbul,
+        "BY" => Tz::Europe__Minsk,
+        "SA" => Tz::Asia__Riyadh,
+        "QA" => Tz::Asia__Qatar,
+        "KW" => Tz::Asia__Kuwait,
+        "BH" => Tz::Asia__Bahrain,
+        "KE" => Tz::Africa__Nairobi,
+        "TZ" => Tz::Africa__Dar_es_Salaam,
+        "UG" => Tz::Africa__Kampala,
+        "AE" => Tz::Asia__Dubai,
+        "OM" => Tz::Asia__Muscat,
+        "GE" => Tz::Asia__Tbilisi,
+        "AM" => Tz::Asia__Yerevan,
+        "AZ" => Tz::Asia__Baku,
+        "PK" => Tz::Asia__Karachi,
+        "UZ" => Tz::Asia__Tashkent,
+        "IN" => Tz::Asia__Kolkata,
+        "LK" => Tz::Asia__Colombo,
+        "NP" => Tz::Asia__Kathmandu,
+        "BD" => Tz::Asia__Dhaka,
+        "TH" => Tz::Asia__Bangkok,
+        "VN" => Tz::Asia__Ho_Chi_Minh,
+        "KH" => Tz::Asia__Phnom_Penh,
+        "LA" => Tz::Asia__Vientiane,
+        "CN" => Tz::Asia__Shanghai,
+        "HK" => Tz::Asia__Hong_Kong,
+        "MO" => Tz::Asia__Macau,
+        "TW" => Tz::Asia__Taipei,
+        "SG" => Tz::Asia__Singapore,
+        "MY" => Tz::Asia__Kuala_Lumpur,
+        "PH" => Tz::Asia__Manila,
+        "MN" => Tz::Asia__Ulaanbaatar,
+        "JP" => Tz::Asia__Tokyo,
+        "KR" => Tz::Asia__Seoul,
+        "NZ" => Tz::Pacific__Auckland,
+        _ => return None,
+    };
+    Some(timezone)
+}
+
+/// The timezone the time of day, weekday, day and month of a stream are computed in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Timezone {
+    /// The timestamps as stored in the history.
+    #[default]
+    Utc,
+    /// A fixed offset in minutes east of UTC.
+    Offset(i32),
+    /// An IANA timezone such as `Europe/Paris`, e.g. the one of the browser. Unlike an offset, it
+    /// follows daylight saving time.
+    Named(Tz),
+    /// The timezone of the country every stream was played from (`conn_country`), which follows
+    /// the listener while travelling. Streams from an unknown country, or from a country that
+    /// spans several timezones, use the given timezone.
+    Country(Tz),
+}
+
+impl Timezone {
+    /// Returns the local time of `ts` for a stream played from `country`. The offset to UTC is
+    /// the one in effect at `ts`, so that daylight saving time is taken into account.
+    pub fn at(&self, ts: DateTime<Utc>, country: &str) -> DateTime<FixedOffset> {
+        match self {
+            Self::Utc => ts.fixed_offset(),
+            Self::Offset(minutes) => ts.with_timezone(
+                &FixedOffset::east_opt(minutes * 60).unwrap_or(FixedOffset::east_opt(0).unwrap()),
+            ),
+            Self::Named(timezone) => ts.with_timezone(timezone).fixed_offset(),
+            Self::Country(fallback) => ts
+                .with_timezone(&country_timezone(country).unwrap_or(*fallback))
+                .fixed_offset(),
+        }
+    }
+
+    /// Returns the local time the stream of `record` ended at.
+    pub fn local(&self, record: &PlaybackRecord) -> DateTime<FixedOffset> {
+        self.at(record.ts, &record.conn_country)
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Offset(_) => write!(f, "UTC{}", self.at(DateTime::UNIX_EPOCH, "").offset()),
+            Self::Named(timezone) => write!(f, "{}", timezone.name()),
+            Self::Country(_) => write!(f, "Country of every stream"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_timezone {
+    use super::*;
+    use crate::data_parser::test_records::record;
+    use chrono::{Datelike, Timelike};
+
+    #[test]
+    fn test_local() {
+        // Saturday 21 October 2023 at 01:30 UTC.
+        let played = record().at(2023, 10, 21, 1, 30).country("US").build();
+        assert_eq!(Timezone::Utc.local(&played).hour(), 1);
+        let fixed = Timezone::Offset(-8 * 60).local(&played);
+        assert_eq!((fixed.day(), fixed.hour()), (20, 17));
+        assert_eq!(Timezone::Offset(330).local(&played).minute(), 0);
+        assert_eq!(Timezone::Offset(-8 * 60).to_string(), "UTC-08:00");
+    }
+
+    #[test]
+    fn test_daylight_saving_time() {
+        let los_angeles = Timezone::Named(Tz::America__Los_Angeles);
+        let summer = record().at(2023, 10, 21, 1, 30).build();
+        let winter = record().at(2023, 12, 21, 1, 30).build();
+        assert_eq!(los_angeles.local(&summer).hour(), 18);
+        assert_eq!(los_angeles.local(&winter).hour(), 17);
+        assert_eq!(los_angeles.to_string(), "America/Los_Angeles");
+    }
+
+    #[test]
+    fn test_country() {
+        let timezone = Timezone::Country(Tz::America__Los_Angeles);
+        let played_from = |country: &str| {
+            let played = record().at(2023, 7, 1, 12, 0).country(country).build();
+            timezone.local(&played).hour()
+        };
+        assert_eq!(played_from("FR"), 14);
+        assert_eq!(played_from("IN"), 17);
+        // The US span several timezones, and "ZZ" is not known.
+        assert_eq!(played_from("US"), 5);
+        assert_eq!(played_from("ZZ"), 5);
+    }
+}
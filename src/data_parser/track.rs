@@ -0,0 +1,151 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::playback_record::PlaybackRecord;
+
+/// How the streams of a record are attributed to a track.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TrackGrouping {
+    /// Every Spotify URI is its own track, so the same song released on a single and on an
+    /// album counts as two tracks.
+    #[default]
+    Uri,
+    /// Tracks with the same title and artist are merged, whatever their URI.
+    TitleAndArtist,
+}
+
+/// The key the streams of a track are grouped on, see [`TrackGrouping`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TrackKey {
+    Uri(String),
+    TitleAndArtist(String, String),
+}
+
+impl TrackGrouping {
+    /// Returns the key of the track `record` played. Records without a URI, such as the ones of
+    /// the account data export, fall back to their title and artist. Returns `None` if the record
+    /// names no track, e.g. because it is a podcast episode.
+    pub fn key(&self, record: &PlaybackRecord) -> Option<TrackKey> {
+        if record.master_metadata_track_name.is_empty() {
+            return None;
+        }
+        match self {
+            Self::Uri if !record.spotify_track_uri.is_empty() => {
+                Some(TrackKey::Uri(record.spotify_track_uri.clone()))
+            }
+            _ => Some(TrackKey::TitleAndArtist(
+                record.master_metadata_track_name.clone(),
+                record.master_metadata_album_artist_name.clone(),
+            )),
+        }
+    }
+}
+
+/// A track as shown in the charts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Track {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+}
+
+impl From<&PlaybackRecord> for Track {
+    fn from(record: &PlaybackRecord) -> Self {
+        Track {
+            title: record.master_metadata_track_name.clone(),
+            artist: record.master_metadata_album_artist_name.clone(),
+            album: record.master_metadata_album_album_name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Track {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.artist.is_empty() {
+            write!(f, "{}", self.title)
+        } else {
+            write!(f, "{} — {}", self.title, self.artist)
+        }
+    }
+}
+
+/// An album, told apart from albums of other artists with the same name.
+///
+/// Spotify only records the artist of every track, not the one of its album, and exports no album
+/// URI. Nothing tells a compilation from albums of different artists that share a name, so the
+/// tracks of a compilation are counted under an album per artist rather than merging unrelated
+/// albums.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Album {
+    pub name: String,
+    pub artist: String,
+}
+
+impl Album {
+    /// Returns the album `record` played, credited to the artist of the track. `None` if the
+    /// album is not known.
+    pub fn of(record: &PlaybackRecord) -> Option<Self> {
+        if record.master_metadata_album_album_name.is_empty() {
+            return None;
+        }
+        Some(Album {
+            name: record.master_metadata_album_album_name.clone(),
+            artist: record.master_metadata_album_artist_name.clone(),
+        })
+    }
+}
+
+impl fmt::Display for Album {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.artist.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} — {}", self.name, self.artist)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_track {
+    use super::*;
+    use crate::data_parser::test_records::record;
+
+    #[test]
+    fn test_key() {
+        let song = || record().track("Song").artist("Artist");
+        let played = song().build();
+        let other_release = song().uri("spotify:track:other").build();
+        assert_ne!(
+            TrackGrouping::Uri.key(&played),
+            TrackGrouping::Uri.key(&other_release)
+        );
+        assert_eq!(
+            TrackGrouping::TitleAndArtist.key(&played),
+            TrackGrouping::TitleAndArtist.key(&other_release)
+        );
+
+        let without_uri = song().uri("").build();
+        assert_eq!(
+            TrackGrouping::Uri.key(&without_uri),
+            TrackGrouping::TitleAndArtist.key(&played)
+        );
+
+        let episode = record().track("").build();
+        assert_eq!(TrackGrouping::Uri.key(&episode), None);
+    }
+
+    #[test]
+    fn test_album() {
+        let played = record().album("Album").artist("Artist").build();
+        let same_name = record().album("Album").artist("Someone Else").build();
+        assert_ne!(Album::of(&played), Album::of(&same_name));
+        assert_eq!(
+            Album::of(&same_name).unwrap().to_string(),
+            "Album — Someone Else"
+        );
+
+        let unknown = record().album("").build();
+        assert_eq!(Album::of(&unknown), None);
+    }
+}
@@ -0,0 +1,67 @@
+pub mod data_parser;
+pub mod storage;
+pub mod views;
+pub mod worker;
+use data_parser::chart::ChartType;
+use views::components::app_wrapper::AppWrapper;
+use views::components::data_context::DataProvider;
+use views::data_error::DataError;
+use views::file_upload::FileUploadView;
+use views::stats::chart::ChartView;
+use views::stats::rank_history::RankHistoryView;
+use views::stats::StatsHome;
+use views::upload_guide::UploadGuide;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(Clone, Routable, PartialEq)]
+enum Route {
+    #[at("/")]
+    Home,
+    #[at("/upload")]
+    Upload,
+    #[at("/upload-guide")]
+    UploadGuide,
+    #[not_found]
+    #[at("/404")]
+    NotFound,
+    #[at("/data-error")]
+    DataError,
+    #[at("/stats")]
+    StatsHome,
+    #[at("/stats/chart/:chart_type")]
+    StatCharts { chart_type: ChartType },
+    #[at("/stats/ranks")]
+    RankHistory,
+}
+
+fn switch(routes: Route) -> Html {
+    match routes {
+        Route::Home | Route::Upload => html! {<AppWrapper><FileUploadView/></AppWrapper>},
+        Route::UploadGuide => html! {<AppWrapper><UploadGuide/> </AppWrapper>},
+        Route::DataError => html! {<AppWrapper><DataError/></AppWrapper>},
+        Route::NotFound => todo!(),
+        Route::StatsHome => html! {<AppWrapper><StatsHome/></AppWrapper>},
+        Route::StatCharts { chart_type } => {
+            html! {<AppWrapper><ChartView chart_type = {chart_type}/></AppWrapper>}
+        }
+        Route::RankHistory => html! {<AppWrapper><RankHistoryView/></AppWrapper>},
+    }
+}
+
+#[function_component(App)]
+fn app() -> Html {
+    html! {
+    <BrowserRouter>
+        <Switch<Route> render={switch} />
+    </BrowserRouter>
+    }
+}
+#[function_component(Root)]
+pub fn root() -> Html {
+    html! {
+        <DataProvider>
+            <App/>
+        </DataProvider>
+    }
+}
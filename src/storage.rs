@@ -0,0 +1,103 @@
+use gloo::console::log;
+use indexed_db_futures::prelude::*;
+use js_sys::Uint8Array;
+use wasm_bindgen::JsValue;
+use web_sys::DomException;
+
+use crate::data_parser::playback_record::PlaybackRecord;
+
+const DATABASE_NAME: &str = "rewrapped";
+const DATABASE_VERSION: u32 = 1;
+const STORE_NAME: &str = "dataset";
+const RECORDS_KEY: &str = "records";
+const FORMAT_KEY: &str = "format";
+/// Version of the serialized record layout. Must be bumped whenever a field of
+/// [`PlaybackRecord`] changes, stored datasets of other versions are discarded.
+const FORMAT_VERSION: u32 = 4;
+
+async fn open_database() -> Result<IdbDatabase, DomException> {
+    let mut request = IdbDatabase::open_u32(DATABASE_NAME, DATABASE_VERSION)?;
+    request.set_on_upgrade_needed(Some(
+        |event: &IdbVersionChangeEvent| -> Result<(), JsValue> {
+            if !event
+                .db()
+                .object_store_names()
+                .any(|name| name == STORE_NAME)
+            {
+                event.db().create_object_store(STORE_NAME)?;
+            }
+            Ok(())
+        },
+    ));
+    request.await
+}
+
+async fn write_records(bytes: Vec<u8>) -> Result<(), DomException> {
+    let database = open_database().await?;
+    let transaction =
+        database.transaction_on_one_with_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+    let store = transaction.object_store(STORE_NAME)?;
+    store.put_key_val_owned(RECORDS_KEY, &Uint8Array::from(&bytes[..]))?;
+    store.put_key_val_owned(FORMAT_KEY, &JsValue::from(FORMAT_VERSION))?;
+    transaction.await.into_result()
+}
+
+async fn read_records() -> Result<Option<Vec<u8>>, DomException> {
+    let database = open_database().await?;
+    let transaction = database.transaction_on_one(STORE_NAME)?;
+    let store = transaction.object_store(STORE_NAME)?;
+    let format = store.get_owned(FORMAT_KEY)?.await?;
+    if format.and_then(|format| format.as_f64()) != Some(FORMAT_VERSION as f64) {
+        return Ok(None);
+    }
+    Ok(store
+        .get_owned(RECORDS_KEY)?
+        .await?
+        .map(|records| Uint8Array::new(&records).to_vec()))
+}
+
+async fn clear_records() -> Result<(), DomException> {
+    let database = open_database().await?;
+    let transaction =
+        database.transaction_on_one_with_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+    transaction.object_store(STORE_NAME)?.clear()?;
+    transaction.await.into_result()
+}
+
+/// Stores `records` in the browser's IndexedDB, replacing any previously stored dataset. The
+/// records are serialized right away and written in the background.
+pub fn store_records(records: &Vec<PlaybackRecord>) {
+    let bytes = match bincode::serialize(records) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log!(format!("Failed to serialize records: {}", e));
+            return;
+        }
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = write_records(bytes).await {
+            log!(format!("Failed to store records: {}", e.message()));
+        }
+    });
+}
+
+/// Loads the dataset stored by [`store_records`]. Returns `None` if nothing (or a dataset of an
+/// older format) is stored.
+pub async fn load_records() -> Option<Vec<PlaybackRecord>> {
+    match read_records().await {
+        Ok(bytes) => bytes.and_then(|bytes| bincode::deserialize(&bytes).ok()),
+        Err(e) => {
+            log!(format!("Failed to load records: {}", e.message()));
+            None
+        }
+    }
+}
+
+/// Deletes the stored dataset in the background.
+pub fn forget_records() {
+    wasm_bindgen_futures::spawn_local(async {
+        if let Err(e) = clear_records().await {
+            log!(format!("Failed to forget records: {}", e.message()));
+        }
+    });
+}
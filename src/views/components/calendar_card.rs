@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use yew::prelude::*;
+
+use crate::{
+    data_parser::calendar::{CalendarDay, DayPlay},
+    views::components::{
+        charts::calendar_heatmap::CalendarHeatmap,
+        data_context::{
+            use_data_worker, ContentContext, DataContext, PeriodContext, TimezoneContext,
+        },
+    },
+    worker::{WorkerInput, WorkerOutput},
+};
+
+/// Card with the calendar of the selected period, and the streams of the day clicked in it.
+#[function_component]
+pub fn CalendarCard() -> Html {
+    let data_context = use_context::<DataContext>().unwrap();
+    let period = use_context::<PeriodContext>().unwrap();
+    let content = use_context::<ContentContext>().unwrap();
+    let timezone = use_context::<TimezoneContext>().unwrap();
+    let days: UseStateHandle<Option<Vec<CalendarDay>>> = use_state(|| None);
+    let selected: UseStateHandle<Option<NaiveDate>> = use_state(|| None);
+    let plays: UseStateHandle<Vec<DayPlay>> = use_state(|| vec![]);
+    let worker = use_data_worker({
+        let days = days.clone();
+        let plays = plays.clone();
+        move |output| match output {
+            WorkerOutput::Calendar(result) => days.set(Some(result)),
+            WorkerOutput::DayPlays(result) => plays.set(result),
+            _ => {}
+        }
+    });
+
+    let deps = (
+        *period,
+        *content,
+        *timezone,
+        data_context.restoring,
+        data_context.summary.revision,
+    );
+    use_effect_with(deps, {
+        let worker = worker.clone();
+        let selected = selected.clone();
+        move |(period, content, timezone, restoring, _)| {
+            if !*restoring {
+                selected.set(None);
+                worker.send(WorkerInput::Calendar {
+                    period: *period,
+                    content: *content,
+                    timezone: *timezone,
+                });
+            }
+        }
+    });
+
+    let on_select = {
+        let selected = selected.clone();
+        let plays = plays.clone();
+        let content = content.clone();
+        let timezone = timezone.clone();
+        Callback::from(move |date: NaiveDate| {
+            plays.set(vec![]);
+            selected.set(Some(date));
+            worker.send(WorkerInput::DayPlays {
+                date,
+                content: *content,
+                timezone: *timezone,
+            });
+        })
+    };
+
+    html! {
+        <div class="card shadow mt-8">
+            <div class="card-body items-center">
+                <h2 class="card-title text-2xl"> { "Calendar" } </h2>
+                if let Some(days) = &*days {
+                    if days.is_empty() {
+                        <p class="text-gray-400"> { "You did not listen to anything during this period." } </p>
+                    } else {
+                        <CalendarHeatmap days={days.clone()} selected={*selected} on_select={on_select} />
+                    }
+                    if let Some(date) = *selected {
+                        <h3 class="text-xl mt-4"> { date.format("%A %d/%m/%y").to_string() } </h3>
+                        if plays.is_empty() {
+                            <p class="text-gray-400"> { "Nothing was played that day." } </p>
+                        } else {
+                            <div class="overflow-x-auto max-h-96 w-full">
+                                <table class="table table-zebra">
+                                    <tbody>
+                                        { for plays.iter().map(|play| html! {
+                                            <tr>
+                                                <td class="text-gray-400"> { play.ts.format("%H:%M").to_string() } </td>
+                                                <td> { &play.name } </td>
+                                                <td class="text-right"> { format!("{}:{:02}", play.ms_played / 60000, play.ms_played / 1000 % 60) } </td>
+                                            </tr>
+                                        }) }
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }
+                } else {
+                    <span class="loading loading-dots loading-lg"></span>
+                }
+            </div>
+        </div>
+    }
+}
@@ -1,4 +1,4 @@
-export function create_bar_chart(data) {
+export function create_bar_chart(id, data) {
   // Parse the data (assumes data is a JSON string passed from Rust)
   const parsedData = JSON.parse(data);
   const series = parsedData.map((item) => item.value);
@@ -67,9 +67,9 @@ export function create_bar_chart(data) {
       },
     },
   };
-  const chartContainer = document.getElementById("bar-chart");
+  const chartContainer = document.getElementById(id);
   if (!chartContainer) {
-    console.error("Error: <div id='bar-chart'> not found in the DOM.");
+    console.error(`Error: <div id='${id}'> not found in the DOM.`);
     return;
   }
   // Clear any existing chart (if necessary)
@@ -29,6 +29,8 @@ impl BarChartData {
 #[derive(Properties, PartialEq, Clone)]
 pub struct BarChartProps {
     pub data: Vec<BarChartData>,
+    #[prop_or(String::from("bar-chart"))]
+    pub id: String,
 }
 
 #[function_component(BarChart)]
@@ -36,18 +38,18 @@ pub fn bar_chart(props: &BarChartProps) -> Html {
     use_effect_with(props.clone(), {
         move |data| {
             let json_data = to_string(&data.data).unwrap();
-            create_bar_chart(&json_data);
+            create_bar_chart(&data.id, &json_data);
             || ()
         }
     });
 
     html! {
-        <div id="bar-chart" class="w-full h-full"></div>
+        <div id={props.id.clone()} class="w-full h-full"></div>
     }
 }
 
 #[wasm_bindgen(module = "/src/views/components/charts/bar_chart.js")]
 extern "C" {
     #[wasm_bindgen(js_name = "create_bar_chart")]
-    pub fn create_bar_chart(data: &str);
+    pub fn create_bar_chart(id: &str, data: &str);
 }
@@ -0,0 +1,73 @@
+use chrono::{Datelike, NaiveDate};
+use yew::prelude::*;
+
+use crate::{data_parser::calendar::CalendarDay, views::stats::chart::get_gradient};
+
+/// Colour of the days without any listening.
+const EMPTY_COLOR: &str = "#2a2a2a";
+/// Number of shades the listening days are spread over.
+const SHADES: usize = 4;
+
+/// Returns the colour of a day, from [`EMPTY_COLOR`] to the darkest shade for the day listened to
+/// the most.
+fn shade(minutes: f32, max: f32, shades: &[String]) -> String {
+    if minutes <= 0.0 || max <= 0.0 {
+        return EMPTY_COLOR.to_string();
+    }
+    let level = ((minutes / max) * SHADES as f32).ceil() as usize;
+    shades[level.clamp(1, SHADES) - 1].clone()
+}
+
+fn tooltip(day: &CalendarDay) -> String {
+    let date = day.date.format("%a %d/%m/%y");
+    match &day.top_track {
+        Some(track) => format!("{}: {} min\nTop track: {}", date, day.minutes, track),
+        None if day.minutes > 0.0 => format!("{}: {} min", date, day.minutes),
+        None => format!("{}: no listening", date),
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CalendarHeatmapProps {
+    /// Consecutive days, in chronological order.
+    pub days: Vec<CalendarDay>,
+    #[prop_or_default]
+    pub selected: Option<NaiveDate>,
+    pub on_select: Callback<NaiveDate>,
+}
+
+/// Calendar with one cell per day, one column per week and Monday on the first row, coloured by
+/// the minutes listened. Unlike the ApexCharts components it is rendered by Yew, so that clicking a
+/// day can be handled in Rust.
+#[function_component(CalendarHeatmap)]
+pub fn calendar_heatmap(props: &CalendarHeatmapProps) -> Html {
+    let max = props.days.iter().map(|day| day.minutes).fold(0.0, f32::max);
+    let shades = get_gradient("#0e4429", "#39d353", SHADES);
+    // Empty cells so that the first day lands on the row of its weekday
+    let offset = props
+        .days
+        .first()
+        .map(|day| day.date.weekday().num_days_from_monday())
+        .unwrap_or(0);
+
+    html! {
+        <div class="overflow-x-auto w-full">
+            <div class="grid grid-rows-7 grid-flow-col gap-1 w-max">
+                { for (0..offset).map(|_| html! { <div class="w-3 h-3"></div> }) }
+                { for props.days.iter().map(|day| {
+                    let date = day.date;
+                    let on_select = props.on_select.clone();
+                    let ring = if props.selected == Some(date) { " ring-2 ring-white" } else { "" };
+                    html! {
+                        <div
+                            class={format!("w-3 h-3 rounded-sm cursor-pointer{}", ring)}
+                            style={format!("background-color: {}", shade(day.minutes, max, &shades))}
+                            title={tooltip(day)}
+                            onclick={Callback::from(move |_| on_select.emit(date))}
+                        ></div>
+                    }
+                }) }
+            </div>
+        </div>
+    }
+}
@@ -1,4 +1,4 @@
-export function create_donut_chart(data) {
+export function create_donut_chart(id, data) {
   // Parse the data (assumes data is a JSON string passed from Rust)
   const parsedData = JSON.parse(data);
 
@@ -67,9 +67,9 @@ export function create_donut_chart(data) {
   };
 
   // Ensure the chart container exists
-  const chartContainer = document.getElementById("pie-chart");
+  const chartContainer = document.getElementById(id);
   if (!chartContainer) {
-    console.error("Error: <div id='pie-chart'> not found in the DOM.");
+    console.error(`Error: <div id='${id}'> not found in the DOM.`);
     return;
   }
 
@@ -16,6 +16,10 @@ pub struct PieChartData {
 #[derive(Properties, PartialEq, Clone)]
 pub struct PieChartProps {
     pub data: Vec<PieChartData>,
+    /// The id of the element the chart is rendered into. Charts that are shown at the same time
+    /// need distinct ids.
+    #[prop_or(String::from("pie-chart"))]
+    pub id: String,
 }
 impl PieChartData {
     pub fn convert(data: Vec<(String, f32)>) -> Vec<Self> {
@@ -36,13 +40,13 @@ pub fn pie_chart(props: &PieChartProps) -> Html {
     use_effect_with(props.clone(), {
         move |data| {
             let json_data = to_string(&data.data).unwrap();
-            create_donut_chart(&json_data);
+            create_donut_chart(&data.id, &json_data);
             || ()
         }
     });
     html! {
 
-    <div id="pie-chart" class="w-full h-full" ></div>
+    <div id={props.id.clone()} class="w-full h-full" ></div>
 
     }
 }
@@ -50,5 +54,5 @@ pub fn pie_chart(props: &PieChartProps) -> Html {
 #[wasm_bindgen(module = "/src/views/components/charts/donut_chart.js")]
 extern "C" {
     #[wasm_bindgen(js_name = "create_donut_chart")]
-    pub fn create_donut_chart(data: &str);
+    pub fn create_donut_chart(id: &str, data: &str);
 }
@@ -0,0 +1,67 @@
+export function create_heatmap_chart(id, data) {
+  // Parse the data (assumes data is a JSON string passed from Rust)
+  const parsedData = JSON.parse(data);
+  // ApexCharts draws the first series at the bottom, reverse the rows to keep the first one on top
+  const series = parsedData.rows
+    .map((row) => ({
+      name: row.name,
+      data: row.cells.map(([x, y]) => ({ x: x, y: y })),
+    }))
+    .reverse();
+  const chartConfig = {
+    series: series,
+    chart: {
+      type: "heatmap",
+      width: "100%",
+      height: "100%",
+      toolbar: {
+        show: false,
+      },
+    },
+    xaxis: {
+      axisBorder: {
+        show: false,
+      },
+      axisTicks: {
+        show: false,
+      },
+    },
+    title: {
+      show: false,
+    },
+    dataLabels: {
+      enabled: false,
+    },
+    colors: [parsedData.color],
+    stroke: {
+      show: true,
+      width: 2,
+      colors: ["#121212"],
+    },
+    legend: {
+      show: false,
+    },
+    grid: {
+      show: false,
+    },
+    plotOptions: {
+      heatmap: {
+        radius: 4,
+        enableShades: true,
+        shadeIntensity: 0.9,
+      },
+    },
+  };
+  const chartContainer = document.getElementById(id);
+  if (!chartContainer) {
+    console.error(`Error: <div id='${id}'> not found in the DOM.`);
+    return;
+  }
+  // Clear any existing chart (if necessary)
+  while (chartContainer.firstChild) {
+    chartContainer.removeChild(chartContainer.firstChild);
+  }
+  // Render the chart in the container
+  const chart = new ApexCharts(chartContainer, chartConfig);
+  chart.render();
+}
@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_json::to_string;
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct HeatmapRow {
+    pub name: String,
+    /// The `(column, value)` cells of the row, from left to right.
+    pub cells: Vec<(String, f32)>,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct HeatmapChartData {
+    pub rows: Vec<HeatmapRow>,
+    pub color: String,
+}
+
+impl HeatmapChartData {
+    /// Splits entries labelled `"<row> <column>"`, e.g. `("Sat 24", 42.0)`, into rows, keeping
+    /// the order of the entries.
+    pub fn convert(data: Vec<(String, f32)>) -> Self {
+        let mut rows: Vec<HeatmapRow> = vec![];
+        for (label, value) in data {
+            let (row, column) = label.rsplit_once(' ').unwrap_or((&label, ""));
+            match rows.last_mut() {
+                Some(last) if last.name == row => last.cells.push((column.to_string(), value)),
+                _ => rows.push(HeatmapRow {
+                    name: row.to_string(),
+                    cells: vec![(column.to_string(), value)],
+                }),
+            }
+        }
+        Self {
+            rows,
+            color: "#10b981".to_string(),
+        }
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct HeatmapChartProps {
+    pub data: HeatmapChartData,
+    #[prop_or(String::from("heatmap-chart"))]
+    pub id: String,
+}
+
+#[function_component(HeatmapChart)]
+pub fn heatmap_chart(props: &HeatmapChartProps) -> Html {
+    use_effect_with(props.clone(), {
+        move |data| {
+            let json_data = to_string(&data.data).unwrap();
+            create_heatmap_chart(&data.id, &json_data);
+            || ()
+        }
+    });
+
+    html! {
+        <div id={props.id.clone()} class="w-full h-full"></div>
+    }
+}
+
+#[wasm_bindgen(module = "/src/views/components/charts/heatmap_chart.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "create_heatmap_chart")]
+    pub fn create_heatmap_chart(id: &str, data: &str);
+}
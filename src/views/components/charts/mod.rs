@@ -1,3 +1,6 @@
 pub mod bar_chart;
+pub mod calendar_heatmap;
 pub mod donut_chart;
+pub mod heatmap_chart;
 pub mod polar_area_chart;
+pub mod rank_chart;
@@ -1,77 +1,77 @@
 
-export function create_polar_area_chart(data) {
+export function create_polar_area_chart(id, data) {
   // Parse the data (assumes data is a JSON string passed from Rust)
   const parsedData = JSON.parse(data);
 
   const series = parsedData.map((item) => item.value);
   const labels = parsedData.map((item) => item.name);
   const colors = parsedData.map((item) => item.color);
-  const chartConfig = {
-    series: series,
-    chart: {
-      type: "polarArea",
-      width: "100%",
-      height: "100%",
-      toolbar: {
-        show: false,
-      },
-    },
-    labels: labels,
-    colors: colors,
-    title: {
-      show: false,
-    },
-    dataLabels: {
-      enabled: true,
-      style: {
-        fontSize: "12px",
-        fontWeight: "bold",
-        color: "#121212",
-      },
-      background: {
-        enabled: true,
-        foreColor: "#121212",
-        padding: 4,
-        borderRadius: 2,
-      },
-      dropShadow: {
-        enabled: false,
-      },
-      formatter: function (val, opts) {
-        return `${opts.w.globals.labels[opts.seriesIndex]}: ${opts.w.globals.series[opts.seriesIndex]} %`;
-      },
-    },
-    stroke: {
-      show: false,
-    },
-    legend: {
-      show: false,
-    },
-    grid: {
-      show: false,
-    },
-    plotOptions: {
-      polarArea: {
-        rings: {
-          strokeWidth: 0,
-        },
-        spokes: {
-          strokeWidth: 0,
-        },
+  const chartConfig = {
+    series: series,
+    chart: {
+      type: "polarArea",
+      width: "100%",
+      height: "100%",
+      toolbar: {
+        show: false,
+      },
+    },
+    labels: labels,
+    colors: colors,
+    title: {
+      show: false,
+    },
+    dataLabels: {
+      enabled: true,
+      style: {
+        fontSize: "12px",
+        fontWeight: "bold",
+        color: "#121212",
+      },
+      background: {
+        enabled: true,
+        foreColor: "#121212",
+        padding: 4,
+        borderRadius: 2,
+      },
+      dropShadow: {
+        enabled: false,
+      },
+      formatter: function (val, opts) {
+        return `${opts.w.globals.labels[opts.seriesIndex]}: ${opts.w.globals.series[opts.seriesIndex]} %`;
+      },
+    },
+    stroke: {
+      show: false,
+    },
+    legend: {
+      show: false,
+    },
+    grid: {
+      show: false,
+    },
+    plotOptions: {
+      polarArea: {
+        rings: {
+          strokeWidth: 0,
+        },
+        spokes: {
+          strokeWidth: 0,
+        },
         polygons: {
           strokeWidth: 3,
           strokeColor: "#121212"
         }
-      },
-      pie: {
+      },
+      pie: {
         borderRadius: 6,
-      },
-    },
+      },
+    },
   };
 
-  const chartContainer = document.getElementById("polar-area-chart");
+  const chartContainer = document.getElementById(id);
   if (!chartContainer) {
-    console.error("Error: <div id='pie-chart'> not found in the DOM.");
+    console.error(`Error: <div id='${id}'> not found in the DOM.`);
     return;
   }
 
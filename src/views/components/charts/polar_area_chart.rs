@@ -44,6 +44,8 @@ impl PolarAreaChartData {
 #[derive(Properties, PartialEq, Clone)]
 pub struct PolarAreaChartProps {
     pub data: Vec<PolarAreaChartData>,
+    #[prop_or(String::from("polar-area-chart"))]
+    pub id: String,
 }
 
 #[function_component(PolarAreaChart)]
@@ -51,18 +53,18 @@ pub fn bar_chart(props: &PolarAreaChartProps) -> Html {
     use_effect_with(props.clone(), {
         move |data| {
             let json_data = to_string(&data.data).unwrap();
-            create_polar_area_chart(&json_data);
+            create_polar_area_chart(&data.id, &json_data);
             || ()
         }
     });
 
     html! {
-        <div id="polar-area-chart" class="w-full h-full"></div>
+        <div id={props.id.clone()} class="w-full h-full"></div>
     }
 }
 
 #[wasm_bindgen(module = "/src/views/components/charts/polar_area_chart.js")]
 extern "C" {
     #[wasm_bindgen(js_name = "create_polar_area_chart")]
-    pub fn create_polar_area_chart(data: &str);
+    pub fn create_polar_area_chart(id: &str, data: &str);
 }
@@ -0,0 +1,89 @@
+export function create_rank_chart(id, data) {
+  // Parse the data (assumes data is a JSON string passed from Rust)
+  const parsedData = JSON.parse(data);
+  const series = parsedData.series.map((item) => ({
+    name: item.name,
+    data: item.ranks,
+  }));
+  const colors = parsedData.series.map((item) => item.color);
+  const maxRank = Math.max(
+    1,
+    ...parsedData.series.flatMap((item) => item.ranks.filter((rank) => rank !== null)),
+  );
+  const chartConfig = {
+    series: series,
+    chart: {
+      type: "line",
+      width: "100%",
+      height: "100%",
+      toolbar: {
+        show: false,
+      },
+      zoom: {
+        enabled: false,
+      },
+      // Draws the trajectories one interval after the other
+      animations: {
+        enabled: true,
+        speed: 1200,
+        animateGradually: {
+          enabled: true,
+          delay: 80,
+        },
+      },
+    },
+    xaxis: {
+      categories: parsedData.intervals,
+      tickAmount: Math.min(parsedData.intervals.length, 12),
+      axisBorder: {
+        show: false,
+      },
+      axisTicks: {
+        show: false,
+      },
+    },
+    yaxis: {
+      // Rank 1 at the top
+      reversed: true,
+      min: 1,
+      max: maxRank,
+      tickAmount: maxRank - 1,
+      labels: {
+        formatter: (value) => `#${Math.round(value)}`,
+      },
+    },
+    tooltip: {
+      shared: true,
+      y: {
+        formatter: (value) => (value === null ? "–" : `#${value}`),
+      },
+    },
+    colors: colors,
+    stroke: {
+      curve: "smooth",
+      width: 3,
+    },
+    markers: {
+      size: 3,
+    },
+    legend: {
+      show: true,
+      position: "bottom",
+    },
+    grid: {
+      show: false,
+    },
+  };
+  const chartContainer = document.getElementById(id);
+  if (!chartContainer) {
+    console.error(`Error: <div id='${id}'> not found in the DOM.`);
+    return;
+  }
+  // Clear any existing chart (if necessary)
+  while (chartContainer.firstChild) {
+    chartContainer.removeChild(chartContainer.firstChild);
+  }
+  // Render the chart in the container
+  const chart = new ApexCharts(chartContainer, chartConfig);
+  chart.render();
+}
@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_json::to_string;
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+use crate::{data_parser::rank_history::RankHistory, views::stats::chart::get_gradient};
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct RankChartSeries {
+    pub name: String,
+    pub ranks: Vec<Option<usize>>,
+    pub color: String,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug, Default)]
+pub struct RankChartData {
+    pub intervals: Vec<String>,
+    pub series: Vec<RankChartSeries>,
+}
+
+impl RankChartData {
+    pub fn convert(history: RankHistory) -> Self {
+        let colours = get_gradient("#10b981", "#6366f1", history.series.len());
+        Self {
+            intervals: history.intervals,
+            series: history
+                .series
+                .into_iter()
+                .zip(colours)
+                .map(|(series, color)| RankChartSeries {
+                    name: series.name,
+                    ranks: series.ranks,
+                    color: color.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct RankChartProps {
+    pub data: RankChartData,
+    #[prop_or(String::from("rank-chart"))]
+    pub id: String,
+}
+
+/// Line chart of the rank of every series after each interval, with the first rank on top.
+#[function_component(RankChart)]
+pub fn rank_chart(props: &RankChartProps) -> Html {
+    use_effect_with(props.clone(), {
+        move |data| {
+            let json_data = to_string(&data.data).unwrap();
+            create_rank_chart(&data.id, &json_data);
+            || ()
+        }
+    });
+
+    html! {
+        <div id={props.id.clone()} class="w-full h-full"></div>
+    }
+}
+
+#[wasm_bindgen(module = "/src/views/components/charts/rank_chart.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "create_rank_chart")]
+    pub fn create_rank_chart(id: &str, data: &str);
+}
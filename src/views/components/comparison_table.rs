@@ -0,0 +1,91 @@
+use yew::prelude::*;
+
+use crate::data_parser::{
+    comparison::{Comparison, RankChange, ValueChange},
+    period::Period,
+};
+
+#[derive(Properties, PartialEq)]
+pub struct ComparisonTableProps {
+    pub comparison: Comparison,
+    pub period: Period,
+    pub previous: Period,
+}
+
+fn rank_change_label(change: &RankChange) -> (String, &'static str) {
+    match change.places_climbed() {
+        None => ("new".to_string(), "text-primary"),
+        Some(0) => ("=".to_string(), "text-gray-400"),
+        Some(places) if places > 0 => (format!("▲ {}", places), "text-success"),
+        Some(places) => (format!("▼ {}", -places), "text-error"),
+    }
+}
+
+fn value_change_label(change: &ValueChange) -> (String, &'static str) {
+    match change.percentage_change() {
+        None => ("new".to_string(), "text-primary"),
+        Some(percentage) if percentage > 0.0 => (format!("+{} %", percentage), "text-success"),
+        Some(percentage) if percentage < 0.0 => (format!("{} %", percentage), "text-error"),
+        Some(_) => ("=".to_string(), "text-gray-400"),
+    }
+}
+
+#[function_component]
+pub fn ComparisonTable(props: &ComparisonTableProps) -> Html {
+    html! {
+        <table class="table text-lg w-3/4 mt-8">
+            {match &props.comparison {
+                Comparison::Ranks(changes) => html! {
+                    <>
+                    <thead>
+                        <tr>
+                            <th> { props.period.to_string() } </th>
+                            <th></th>
+                            <th> { props.previous.to_string() } </th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for changes.iter().map(|change| {
+                            let (label, class) = rank_change_label(change);
+                            html! {
+                                <tr>
+                                    <td> { format!("#{}", change.rank) } </td>
+                                    <td> { change.name.clone() } </td>
+                                    <td> { change.previous_rank.map(|rank| format!("#{}", rank)).unwrap_or_else(|| "-".to_string()) } </td>
+                                    <td class={class}> { label } </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                    </>
+                },
+                Comparison::Values(changes) => html! {
+                    <>
+                    <thead>
+                        <tr>
+                            <th></th>
+                            <th> { props.period.to_string() } </th>
+                            <th> { props.previous.to_string() } </th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for changes.iter().map(|change| {
+                            let (label, class) = value_change_label(change);
+                            html! {
+                                <tr>
+                                    <td> { change.name.clone() } </td>
+                                    <td> { change.value } </td>
+                                    <td> { change.previous_value } </td>
+                                    <td class={class}> { label } </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                    </>
+                },
+            }}
+        </table>
+    }
+}
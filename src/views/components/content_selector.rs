@@ -0,0 +1,39 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::data_parser::content::ContentFilter;
+
+use super::data_context::ContentContext;
+
+/// Selects the kinds of content the charts are computed for. The podcast charts ignore it.
+#[function_component]
+pub fn ContentSelector() -> Html {
+    let content = use_context::<ContentContext>().unwrap();
+
+    let on_select = {
+        let content = content.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(filter) = select
+                .value()
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| ContentFilter::ALL.get(i))
+            {
+                content.set(*filter);
+            }
+        })
+    };
+
+    html! {
+        <div class="flex flex-row items-center justify-center gap-4 mb-8">
+            <select class="select select-bordered text-xl" onchange={on_select}>
+                { for ContentFilter::ALL.iter().enumerate().map(|(i, filter)| html! {
+                    <option value={i.to_string()} selected={*content == *filter}>
+                        { filter.to_string() }
+                    </option>
+                }) }
+            </select>
+        </div>
+    }
+}
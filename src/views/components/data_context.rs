@@ -1,20 +1,97 @@
+use std::rc::Rc;
+
+use chrono_tz::Tz;
+use gloo::worker::{Spawnable, WorkerBridge};
+use js_sys::{Array, Date, Intl, Object, Reflect};
+use wasm_bindgen::JsValue;
 use yew::prelude::*;
 
-use crate::data_parser::playback_record::PlaybackRecord;
+use crate::{
+    data_parser::{
+        content::ContentFilter, dataset::DatasetSummary, error::ParseError, period::Period,
+        timezone::Timezone,
+    },
+    worker::{DataWorker, WorkerInput, WorkerOutput, WORKER_PATH},
+};
 
+/// The dataset as the views see it. The records themselves live in the data worker, which sends
+/// a summary of them after every change.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Data {
-    pub inner: Vec<PlaybackRecord>,
+    pub summary: DatasetSummary,
+    /// The error of the last failed import, shown by the `DataError` view.
+    pub error: Option<ParseError>,
+    /// Whether the dataset stored in the browser is still being loaded. Views should wait for it
+    /// before deciding that there is no data.
+    pub restoring: bool,
+}
+
+/// What the data worker, or a failed import, tells the views about the dataset.
+pub enum DataEvent {
+    /// The dataset changed, or was summarized for another timezone.
+    Summarized(DatasetSummary),
+    /// An import failed. The dataset is left untouched.
+    Failed(ParseError),
 }
 
 impl Reducible for Data {
-    type Action = Vec<PlaybackRecord>;
+    type Action = DataEvent;
 
     fn reduce(self: std::rc::Rc<Self>, action: Self::Action) -> std::rc::Rc<Self> {
-        Self { inner: action }.into()
+        match action {
+            DataEvent::Failed(error) => Self {
+                summary: self.summary.clone(),
+                error: Some(error),
+                restoring: self.restoring,
+            },
+            DataEvent::Summarized(summary) => Self {
+                // The error is kept until the dataset changes.
+                error: if summary.revision == self.summary.revision {
+                    self.error.clone()
+                } else {
+                    None
+                },
+                summary,
+                restoring: false,
+            },
+        }
+        .into()
     }
 }
 pub type DataContext = UseReducerHandle<Data>;
+/// The period every chart is computed for.
+pub type PeriodContext = UseStateHandle<Period>;
+/// The kinds of content every chart is computed for.
+pub type ContentContext = UseStateHandle<ContentFilter>;
+/// The timezone the hours, days and months of every chart are computed in.
+pub type TimezoneContext = UseStateHandle<Timezone>;
+/// The bridge to the worker that owns the dataset, parses uploads and runs the queries. The
+/// dataset is changed by sending it a [`WorkerInput::Update`].
+pub type WorkerContext = Rc<WorkerBridge<DataWorker>>;
+
+/// Returns the IANA timezone of the browser, or its current offset if the browser does not report
+/// a timezone chrono-tz knows.
+pub fn browser_timezone() -> Timezone {
+    let options = Intl::DateTimeFormat::new(&Array::new(), &Object::new()).resolved_options();
+    Reflect::get(&options, &JsValue::from_str("timeZone"))
+        .ok()
+        .and_then(|name| name.as_string())
+        .and_then(|name| name.parse::<Tz>().ok())
+        .map(Timezone::Named)
+        // `getTimezoneOffset` counts the minutes west of UTC
+        .unwrap_or_else(|| Timezone::Offset(-Date::new_0().get_timezone_offset() as i32))
+}
+
+/// Connects to the data worker. `on_output` receives the responses to the requests sent through
+/// the returned bridge; it is set on the first render and never replaced.
+#[hook]
+pub fn use_data_worker<F>(on_output: F) -> Rc<WorkerBridge<DataWorker>>
+where
+    F: Fn(WorkerOutput) + 'static,
+{
+    let worker = use_context::<WorkerContext>().unwrap();
+    use_memo((), move |_| worker.fork(Some(on_output)))
+}
 
 #[derive(Properties, Debug, PartialEq)]
 pub struct DataProviderProps {
@@ -24,11 +101,47 @@ pub struct DataProviderProps {
 
 #[function_component]
 pub fn DataProvider(props: &DataProviderProps) -> Html {
-    let data = use_reducer(|| Data { inner: vec![] });
+    let data = use_reducer(|| Data {
+        summary: DatasetSummary::default(),
+        error: None,
+        restoring: true,
+    });
+    let timezone = use_state(browser_timezone);
+
+    let worker: WorkerContext = {
+        let dispatcher = data.dispatcher();
+        use_memo((), move |_| {
+            DataWorker::spawner()
+                .callback(move |output| {
+                    if let WorkerOutput::Dataset(summary) = output {
+                        dispatcher.dispatch(DataEvent::Summarized(summary));
+                    }
+                })
+                .spawn(WORKER_PATH)
+        })
+    };
+
+    {
+        let worker = worker.clone();
+        use_effect_with(*timezone, move |timezone| {
+            worker.send(WorkerInput::Subscribe(*timezone));
+        });
+    }
+
+    let period = use_state(Period::default);
+    let content = use_state(ContentFilter::default);
 
     html! {
         <ContextProvider<DataContext> context={data}>
-            {props.children.clone()}
+            <ContextProvider<WorkerContext> context={worker}>
+                <ContextProvider<PeriodContext> context={period}>
+                    <ContextProvider<ContentContext> context={content}>
+                        <ContextProvider<TimezoneContext> context={timezone}>
+                            {props.children.clone()}
+                        </ContextProvider<TimezoneContext>>
+                    </ContextProvider<ContentContext>>
+                </ContextProvider<PeriodContext>>
+            </ContextProvider<WorkerContext>>
         </ContextProvider<DataContext>>
     }
 }
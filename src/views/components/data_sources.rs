@@ -0,0 +1,64 @@
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+use crate::{
+    data_parser::{dataset::DataAction, report::with_thousands_separator},
+    views::components::data_context::{DataContext, WorkerContext},
+    worker::WorkerInput,
+    Route,
+};
+
+/// Lists the files the dataset was imported from, with a button to remove the records of each
+/// file and a button to add more files.
+#[function_component]
+pub fn DataSources() -> Html {
+    let data_context = use_context::<DataContext>().unwrap();
+    let worker = use_context::<WorkerContext>().unwrap();
+    let navigator = use_navigator().unwrap();
+    let sources = data_context.summary.sources.clone();
+    // Records are only removed with the last of their sources, so the dataset is only emptied
+    // when a single file is left.
+    let is_last = sources.len() == 1;
+
+    let on_add = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| navigator.push(&Route::Upload))
+    };
+
+    html! {
+        <details class="text-base text-gray-400 mt-8">
+            <summary class="cursor-pointer hover:underline text-center">
+                { format!("Imported from {} files", sources.len()) }
+            </summary>
+            <table class="table mt-2">
+                <tbody>
+                    { for sources.into_iter().map(|(source, count)| {
+                        let onclick = {
+                            let worker = worker.clone();
+                            let navigator = navigator.clone();
+                            let source = source.clone();
+                            Callback::from(move |_| {
+                                worker.send(WorkerInput::Update(DataAction::RemoveSource(source.clone())));
+                                if is_last {
+                                    navigator.push(&Route::Upload);
+                                }
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td> { if source.is_empty() { "Unknown file".to_string() } else { source } } </td>
+                                <td> { format!("{} records", with_thousands_separator(count)) } </td>
+                                <td>
+                                    <button {onclick} class="hover:underline"> { "Remove" } </button>
+                                </td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+            <div class="flex justify-center mt-2">
+                <button onclick={on_add} class="hover:underline"> { "Add more files" } </button>
+            </div>
+        </details>
+    }
+}
@@ -1,36 +1,100 @@
+use crate::data_parser::history_file::{FileKind, HistoryFile};
 use crate::data_parser::playback_record::PlaybackRecord;
 
-use super::super::file_upload::FileState;
-use _FileInputProps::{on_file_ammount, on_file_loaded};
+use super::super::file_upload::{FileProgress, FileState};
+use super::progress_bar::ProgressBar;
 use gloo::console::log;
-use gloo::file::callbacks::{read_as_text, FileReader};
+use gloo::file::callbacks::{read_as_bytes, read_as_text, FileReader};
 use gloo::file::{File, FileReadError};
 use gloo::utils::errors::JsError;
-use js_sys::Promise;
+use js_sys::{Array, Function, Promise};
 use std::collections::HashMap;
-use wasm_bindgen::JsValue;
-use wasm_bindgen_futures;
-use web_sys::{DragEvent, Event, HtmlInputElement};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{self, JsFuture};
+use web_sys::{
+    DragEvent, Event, FileSystemDirectoryEntry, FileSystemEntry, FileSystemFileEntry,
+    HtmlInputElement,
+};
 use yew::prelude::*;
 use yew_router::navigator;
 
 #[derive(Properties, PartialEq)]
 pub struct FileInputProps {
-    pub on_file_loaded: Callback<Vec<u8>>,
+    /// Called with the files to import once the user starts processing.
+    pub on_submit: Callback<Vec<HistoryFile>>,
     pub on_change_upload_state: Callback<FileState>,
-    pub on_file_ammount: Callback<usize>,
 }
-pub struct FileDetails {
+
+/// Size of the slices the files are read in, so that the progress can be reported while reading.
+const CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// File extensions that are queued when a folder is dropped or selected, other files such as
+/// `ReadMe.pdf` are ignored.
+const ACCEPTED_EXTENSIONS: [&str; 2] = [".json", ".zip"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum FileStatus {
+    Reading,
+    Read(FileKind),
+    Unreadable(String),
+}
+
+/// A file in the upload queue and the bytes of it that were read so far.
+struct QueuedFile {
+    id: usize,
+    file: File,
     data: Vec<u8>,
+    status: FileStatus,
+}
+
+impl QueuedFile {
+    fn progress(&self) -> FileProgress {
+        FileProgress {
+            name: self.file.name(),
+            loaded: self.data.len() as u64,
+            size: self.file.size(),
+        }
+    }
+
+    /// Whether the file is read and can be imported.
+    fn is_importable(&self) -> bool {
+        matches!(&self.status, FileStatus::Read(kind) if *kind != FileKind::Unknown)
+    }
+
+    fn status_label(&self) -> String {
+        match &self.status {
+            FileStatus::Reading => format!("Reading {}%", self.progress().percentage()),
+            FileStatus::Read(FileKind::Unknown) => "Not a streaming history, skipped".to_string(),
+            FileStatus::Read(_) => "Ready".to_string(),
+            FileStatus::Unreadable(reason) => format!("Unreadable: {}", reason),
+        }
+    }
+
+    fn kind_label(&self) -> String {
+        match &self.status {
+            FileStatus::Reading => "…".to_string(),
+            FileStatus::Read(kind) => kind.to_string(),
+            FileStatus::Unreadable(_) => FileKind::Unknown.to_string(),
+        }
+    }
 }
 
 pub enum Msg {
-    Loaded(FileDetails),
-    Files(Option<web_sys::FileList>),
+    /// The next slice of the queued file with the given id was read.
+    Chunk(usize, Result<Vec<u8>, FileReadError>),
+    Files(Vec<File>),
+    Remove(usize),
+    Dragging(bool),
+    Submit,
+    Cancel,
 }
 pub struct FileInput {
-    readers: HashMap<String, FileReader>,
-    uploading: bool,
+    /// The in-flight reader of every queued file that is not completely read yet. Dropping a
+    /// reader aborts it.
+    readers: HashMap<usize, FileReader>,
+    queue: Vec<QueuedFile>,
+    next_id: usize,
+    dragging: bool,
 }
 async fn read_file_as_text(file: File) -> Result<String, JsValue> {
     let (tx, rx) = std::sync::mpsc::channel();
@@ -48,13 +112,93 @@ async fn read_file_as_text(file: File) -> Result<String, JsValue> {
         Err(_) => Err(JsValue::from_str("Failed to receive file read result")),
     }
 }
-impl From<Result<Vec<u8>, FileReadError>> for Msg {
-    fn from(value: Result<Vec<u8>, FileReadError>) -> Msg {
-        match value {
-            Ok(s) => Msg::Loaded(FileDetails { data: s }),
-            Err(_) => Msg::Loaded(FileDetails { data: vec![] }),
+
+/// Turns a call of the callback based file system API into a future.
+async fn callback_future<F>(call: F) -> Result<JsValue, JsValue>
+where
+    F: FnOnce(&Function, &Function),
+{
+    let mut call = Some(call);
+    let promise = Promise::new(&mut |resolve, reject| {
+        if let Some(call) = call.take() {
+            call(&resolve, &reject);
+        }
+    });
+    JsFuture::from(promise).await
+}
+
+/// Collects the files of a drop, descending into dropped folders.
+async fn collect_dropped_files(entries: Vec<FileSystemEntry>) -> Vec<File> {
+    let mut files = vec![];
+    let mut entries = entries;
+    while let Some(entry) = entries.pop() {
+        if entry.is_file() {
+            let entry: FileSystemFileEntry = entry.unchecked_into();
+            let file = callback_future(|resolve, reject| {
+                entry.file_with_callback_and_callback(resolve, reject)
+            })
+            .await;
+            if let Ok(file) = file {
+                files.push(File::from(file.unchecked_into::<web_sys::File>()));
+            }
+        } else if entry.is_directory() {
+            let reader = entry
+                .unchecked_into::<FileSystemDirectoryEntry>()
+                .create_reader();
+            // The entries of a folder are returned in batches, an empty batch marks the end.
+            loop {
+                let batch = callback_future(|resolve, reject| {
+                    let _ = reader.read_entries_with_callback_and_callback(resolve, reject);
+                })
+                .await;
+                let batch: Array = match batch {
+                    Ok(batch) => batch.unchecked_into(),
+                    Err(_) => break,
+                };
+                if batch.length() == 0 {
+                    break;
+                }
+                entries.extend(batch.iter().map(|entry| entry.unchecked_into()));
+            }
         }
     }
+    files
+}
+
+/// Formats a file size, e.g. `12.3 MB`.
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1048575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1048576.0),
+    }
+}
+
+impl FileInput {
+    fn read_next_chunk(&mut self, ctx: &Context<Self>, id: usize) {
+        let Some(queued) = self.queue.iter().find(|queued| queued.id == id) else {
+            return;
+        };
+        let start = queued.data.len() as u64;
+        let end = (start + CHUNK_SIZE).min(queued.file.size());
+        let link = ctx.link().clone();
+        let reader = read_as_bytes(&queued.file.slice(start, end), move |result| {
+            link.send_message(Msg::Chunk(id, result))
+        });
+        self.readers.insert(id, reader);
+    }
+
+    fn emit_state(&self, ctx: &Context<Self>) {
+        ctx.props()
+            .on_change_upload_state
+            .emit(if self.queue.is_empty() {
+                FileState::NotUploaded
+            } else {
+                FileState::Uploading {
+                    files: self.queue.iter().map(QueuedFile::progress).collect(),
+                }
+            });
+    }
 }
 impl Component for FileInput {
     type Message = Msg;
@@ -63,68 +207,222 @@ impl Component for FileInput {
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
             readers: HashMap::default(),
-            uploading: false,
+            queue: vec![],
+            next_id: 0,
+            dragging: false,
         }
     }
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::Loaded(file) => {
-                ctx.props().on_file_loaded.emit(file.data);
-                log!("Sending file...");
+            Msg::Chunk(id, result) => {
+                let Some(queued) = self.queue.iter_mut().find(|queued| queued.id == id) else {
+                    // The file was removed while the slice was read.
+                    return false;
+                };
+                match result {
+                    Ok(bytes) => {
+                        queued.data.extend(bytes);
+                        if queued.data.len() as u64 >= queued.file.size() {
+                            let kind = FileKind::detect(&queued.file.name(), &queued.data);
+                            queued.status = FileStatus::Read(kind);
+                        }
+                    }
+                    Err(e) => {
+                        queued.data.clear();
+                        queued.status = FileStatus::Unreadable(e.to_string());
+                    }
+                }
+                if queued.status == FileStatus::Reading {
+                    self.read_next_chunk(ctx, id);
+                } else {
+                    self.readers.remove(&id);
+                    log!(format!("Read file: {}", queued.file.name()).as_str());
+                }
+                self.emit_state(ctx);
                 true
             }
             Msg::Files(files) => {
-                let files = gloo::file::FileList::from(files.expect("files"));
-                ctx.props().on_file_ammount.emit(files.len());
-                self.uploading = true;
-                for file in files.iter() {
-                    ctx.props()
-                        .on_change_upload_state
-                        .emit(FileState::Uploading);
-
-                    let name = file.name().clone();
+                self.dragging = false;
+                for file in files {
+                    let name = file.name();
+                    if !ACCEPTED_EXTENSIONS
+                        .iter()
+                        .any(|extension| name.to_lowercase().ends_with(extension))
+                    {
+                        continue;
+                    }
                     gloo::console::log!(format!("Registering file: {}", name).as_str());
-                    ctx.link()
-                        .send_future(gloo::file::futures::read_as_bytes(file));
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    self.queue.push(QueuedFile {
+                        id,
+                        data: Vec::with_capacity(file.size() as usize),
+                        file,
+                        status: FileStatus::Reading,
+                    });
+                    self.read_next_chunk(ctx, id);
                 }
+                self.emit_state(ctx);
+                true
+            }
+            Msg::Remove(id) => {
+                self.readers.remove(&id);
+                self.queue.retain(|queued| queued.id != id);
+                self.emit_state(ctx);
+                true
+            }
+            Msg::Dragging(dragging) => {
+                let changed = self.dragging != dragging;
+                self.dragging = dragging;
+                changed
+            }
+            Msg::Submit => {
+                let files = self
+                    .queue
+                    .drain(..)
+                    .filter(QueuedFile::is_importable)
+                    .map(|queued| HistoryFile::new(queued.file.name(), queued.data))
+                    .collect();
+                ctx.props().on_submit.emit(files);
+                false
+            }
+            Msg::Cancel => {
+                self.readers.clear();
+                self.queue.clear();
+                self.emit_state(ctx);
                 true
             }
         }
     }
     fn view(&self, ctx: &Context<Self>) -> Html {
-        html! {
+        let on_select = ctx.link().callback(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let files = input
+                .files()
+                .map(|files| gloo::file::FileList::from(files).to_vec())
+                .unwrap_or_default();
+            // Allows selecting the same file again after removing it.
+            input.set_value("");
+            Msg::Files(files)
+        });
+        let on_drop = ctx.link().callback_future(move |e: DragEvent| {
+            e.prevent_default();
+            // The items of a drop can only be accessed while the event is dispatched.
+            let entries: Vec<FileSystemEntry> = e
+                .data_transfer()
+                .map(|data| data.items())
+                .map(|items| {
+                    (0..items.length())
+                        .filter_map(|index| items.get(index))
+                        .filter_map(|item| item.webkit_get_as_entry().ok().flatten())
+                        .collect()
+                })
+                .unwrap_or_default();
+            async move { Msg::Files(collect_dropped_files(entries).await) }
+        });
+        let reading = self
+            .queue
+            .iter()
+            .any(|queued| queued.status == FileStatus::Reading);
+        let importable = self.queue.iter().any(QueuedFile::is_importable);
 
-                           <div class="flex items-center justify-center w-full">
-            if !self.uploading{
-              <label
-                for="file-upload"
-                class="flex items-center justify-center w-1/2 text-xl font-semibold py-3 mbtn"
-              >
-                        {"Select Files"}
-            </label>
-
-              <input
-                id="file-upload"
-                type="file"
-                accept="image/*,video/*"
-                class="hidden"
-                multiple={true}
-                accept=".json"
-                onchange={ctx.link().callback(move |e: Event| {
-                  let input: HtmlInputElement = e.target_unchecked_into();
-                  Msg::Files(input.files())
+        html! {
+            <div class="flex flex-col items-center justify-center w-full">
+              <div
+                class={classes!(
+                    "flex", "flex-col", "items-center", "justify-center", "w-1/2", "p-8",
+                    "border-2", "border-dashed", "rounded-lg",
+                    if self.dragging { "border-primary" } else { "border-gray-400" }
+                )}
+                ondragover={ctx.link().callback(|e: DragEvent| {
+                    e.prevent_default();
+                    Msg::Dragging(true)
                 })}
-              />}
-            else {
-                <div class ="flex flex-col items-center justify-center w-full h-full ">
-                    <div class="flex items-center justify-center w-1/2 text-xl font-semibold py-3 mbtn">{"Uploading... "}</div>
-                    <p class="text-base text-gray-400 mt-8 hover:underline text-center">
-                        { "This may take a while..." }
-                    </p>
+                ondragleave={ctx.link().callback(|_: DragEvent| Msg::Dragging(false))}
+                ondrop={on_drop}
+              >
+                <p class="text-xl text-gray-400 mb-4"> { "Drop your files or folders here" } </p>
+                <div class="flex flex-row gap-4">
+                  <label
+                    for="file-upload"
+                    class="flex items-center justify-center text-xl font-semibold py-3 px-6 mbtn"
+                  >
+                            {"Select Files"}
+                  </label>
+                  <input
+                    id="file-upload"
+                    type="file"
+                    class="hidden"
+                    multiple={true}
+                    accept=".json,.zip"
+                    onchange={on_select.clone()}
+                  />
+                  <label
+                    for="folder-upload"
+                    class="flex items-center justify-center text-xl font-semibold py-3 px-6 mbtn"
+                  >
+                            {"Select Folder"}
+                  </label>
+                  <input
+                    id="folder-upload"
+                    type="file"
+                    class="hidden"
+                    webkitdirectory="true"
+                    onchange={on_select}
+                  />
                 </div>
-            }
+              </div>
+              if !self.queue.is_empty() {
+                if reading {
+                    <ProgressBar
+                        done={self.queue.iter().map(|queued| queued.data.len() as u64).sum::<u64>() / 1024}
+                        total={self.queue.iter().map(|queued| queued.file.size()).sum::<u64>() / 1024}
+                        unit="KB"
+                    />
+                }
+                <table class="table w-1/2 mt-4 text-base">
+                    <thead>
+                        <tr>
+                            <th> { "File" } </th>
+                            <th> { "Size" } </th>
+                            <th> { "Type" } </th>
+                            <th> { "Status" } </th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for self.queue.iter().map(|queued| {
+                            let id = queued.id;
+                            html! {
+                                <tr key={id}>
+                                    <td> { queued.file.name() } </td>
+                                    <td> { format_size(queued.file.size()) } </td>
+                                    <td> { queued.kind_label() } </td>
+                                    <td> { queued.status_label() } </td>
+                                    <td>
+                                        <button onclick={ctx.link().callback(move |_| Msg::Remove(id))} class="text-gray-400 hover:underline">
+                                            { "Remove" }
+                                        </button>
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+                <div class="flex flex-row gap-4 mt-4">
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::Submit)}
+                        disabled={reading || !importable}
+                        class="text-xl font-semibold py-3 px-6 mbtn"
+                    >
+                        { "Process files" }
+                    </button>
+                    <button onclick={ctx.link().callback(|_| Msg::Cancel)} class="text-base text-gray-400 hover:underline">
+                        { "Cancel" }
+                    </button>
+                </div>
+              }
             </div>
-
         }
     }
 }
@@ -0,0 +1,24 @@
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+use crate::{
+    data_parser::dataset::DataAction, views::components::data_context::WorkerContext,
+    worker::WorkerInput, Route,
+};
+
+/// Button that deletes the dataset, which also removes it from the browser storage, then returns
+/// to the upload page.
+#[function_component]
+pub fn ForgetDataButton() -> Html {
+    let worker = use_context::<WorkerContext>().unwrap();
+    let navigator = use_navigator().unwrap();
+    let onclick = Callback::from(move |_| {
+        worker.send(WorkerInput::Update(DataAction::Clear));
+        navigator.push(&Route::Upload);
+    });
+    html! {
+        <button {onclick} class="text-base text-gray-400 hover:underline">
+            { "Forget my data" }
+        </button>
+    }
+}
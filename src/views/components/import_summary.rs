@@ -0,0 +1,57 @@
+use yew::prelude::*;
+
+use crate::data_parser::report::{with_thousands_separator, ImportReport};
+
+#[derive(Properties, PartialEq)]
+pub struct ImportSummaryProps {
+    pub report: ImportReport,
+}
+
+#[function_component]
+pub fn ImportSummary(props: &ImportSummaryProps) -> Html {
+    let report = &props.report;
+    html! {
+        <div class="flex flex-col items-center text-base text-gray-400 mt-8 text-center">
+            <p>
+                { format!(
+                    "Imported {} records, skipped {}, dropped {} duplicates",
+                    with_thousands_separator(report.imported),
+                    with_thousands_separator(report.skipped()),
+                    with_thousands_separator(report.duplicates)
+                ) }
+            </p>
+            if report.skipped() > 0 {
+                <details class="mt-2">
+                    <summary class="cursor-pointer hover:underline"> { "See details" } </summary>
+                    <ul class="mt-2 text-left">
+                        { for report.rejections_per_reason().into_iter().map(|(reason, count)| html! {
+                            <li> { format!("{} × {}", with_thousands_separator(count), reason) } </li>
+                        }) }
+                    </ul>
+                </details>
+            }
+            if report.files.len() > 1 {
+                <table class="table mt-4 text-base">
+                    <thead>
+                        <tr>
+                            <th> { "File" } </th>
+                            <th> { "Imported" } </th>
+                            <th> { "Skipped" } </th>
+                            <th> { "Duplicates" } </th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for report.files.iter().map(|file| html! {
+                            <tr key={file.file.clone()}>
+                                <td> { &file.file } </td>
+                                <td> { with_thousands_separator(file.imported) } </td>
+                                <td> { with_thousands_separator(file.rejected) } </td>
+                                <td> { with_thousands_separator(file.duplicates) } </td>
+                            </tr>
+                        }) }
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
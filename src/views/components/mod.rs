@@ -1,4 +1,14 @@
 pub mod app_wrapper;
+pub mod calendar_card;
 pub mod charts;
+pub mod comparison_table;
+pub mod content_selector;
 pub mod data_context;
+pub mod data_sources;
 pub mod file_upload;
+pub mod forget_data;
+pub mod import_summary;
+pub mod period_selector;
+pub mod progress_bar;
+pub mod streaks_card;
+pub mod timezone_selector;
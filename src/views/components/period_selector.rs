@@ -0,0 +1,85 @@
+use chrono::NaiveDate;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::data_parser::period::Period;
+
+use super::data_context::{DataContext, PeriodContext};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).ok()
+}
+
+#[function_component]
+pub fn PeriodSelector() -> Html {
+    let data_context = use_context::<DataContext>().unwrap();
+    let period = use_context::<PeriodContext>().unwrap();
+    let years = data_context.summary.years.clone();
+    let custom = matches!(*period, Period::Range { .. });
+
+    let on_select = {
+        let period = period.clone();
+        let years = years.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            match select.value().as_str() {
+                "all" => period.set(Period::AllTime),
+                "custom" => {
+                    let last = years.first().copied().unwrap_or(2008);
+                    let first = years.last().copied().unwrap_or(last);
+                    period.set(Period::Range {
+                        start: NaiveDate::from_ymd_opt(first, 1, 1).unwrap(),
+                        end: NaiveDate::from_ymd_opt(last, 12, 31).unwrap(),
+                    })
+                }
+                year => {
+                    if let Ok(year) = year.parse() {
+                        period.set(Period::Year(year));
+                    }
+                }
+            }
+        })
+    };
+    let on_range_change = |is_start: bool| {
+        let period = period.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let (Period::Range { start, end }, Some(date)) =
+                (*period, parse_date(&input.value()))
+            {
+                let (start, end) = if is_start { (date, end) } else { (start, date) };
+                if start <= end {
+                    period.set(Period::Range { start, end });
+                }
+            }
+        })
+    };
+    let (start, end) = match *period {
+        Period::Range { start, end } => (
+            start.format(DATE_FORMAT).to_string(),
+            end.format(DATE_FORMAT).to_string(),
+        ),
+        _ => (String::new(), String::new()),
+    };
+
+    html! {
+        <div class="flex flex-row items-center justify-center gap-4 mb-8">
+            <select class="select select-bordered text-xl" onchange={on_select}>
+                <option value="all" selected={*period == Period::AllTime}> { "All time" } </option>
+                { for years.iter().map(|year| html! {
+                    <option value={year.to_string()} selected={*period == Period::Year(*year)}>
+                        { year }
+                    </option>
+                }) }
+                <option value="custom" selected={custom}> { "Custom range" } </option>
+            </select>
+            if custom {
+                <input type="date" class="input input-bordered" value={start} onchange={on_range_change(true)} />
+                <span> { "–" } </span>
+                <input type="date" class="input input-bordered" value={end} onchange={on_range_change(false)} />
+            }
+        </div>
+    }
+}
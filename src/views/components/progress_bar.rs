@@ -0,0 +1,51 @@
+use yew::prelude::*;
+
+use crate::data_parser::report::with_thousands_separator;
+
+#[derive(Properties, PartialEq)]
+pub struct ProgressBarProps {
+    pub done: u64,
+    pub total: u64,
+    /// What is counted, e.g. "MB" or "records".
+    pub unit: AttrValue,
+}
+
+/// Formats the time left as "about 3 min left" or "about 20 s left".
+fn format_time_left(ms: f64) -> String {
+    let seconds = (ms / 1000.0).ceil() as u64;
+    if seconds >= 60 {
+        format!("about {} min left", (seconds + 59) / 60)
+    } else {
+        format!("about {} s left", seconds)
+    }
+}
+
+/// A progress bar that estimates the time left from the progress made since it was first shown.
+#[function_component]
+pub fn ProgressBar(props: &ProgressBarProps) -> Html {
+    let started_at = use_state(js_sys::Date::now);
+    let elapsed = js_sys::Date::now() - *started_at;
+    let time_left = if props.done > 0 && props.done < props.total {
+        let remaining = (props.total - props.done) as f64;
+        Some(format_time_left(elapsed * remaining / props.done as f64))
+    } else {
+        None
+    };
+
+    html! {
+        <div class="flex flex-col items-center w-1/2">
+            <progress class="progress progress-primary w-full" value={props.done.to_string()} max={props.total.max(1).to_string()}></progress>
+            <p class="text-base text-gray-400 mt-2 text-center">
+                { format!(
+                    "{} / {} {}",
+                    with_thousands_separator(props.done as usize),
+                    with_thousands_separator(props.total as usize),
+                    props.unit
+                ) }
+                if let Some(time_left) = time_left {
+                    { format!(", {}", time_left) }
+                }
+            </p>
+        </div>
+    }
+}
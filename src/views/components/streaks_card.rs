@@ -0,0 +1,120 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    data_parser::streaks::{Streak, StreakStats},
+    views::components::data_context::{
+        use_data_worker, ContentContext, DataContext, PeriodContext, TimezoneContext,
+    },
+    worker::{WorkerInput, WorkerOutput},
+};
+
+/// Formats a streak as e.g. "12 days (03/01/23 – 14/01/23)".
+fn format_streak(streak: &Option<Streak>) -> String {
+    match streak {
+        Some(streak) if streak.days() == 1 => {
+            format!("1 day ({})", streak.start.format("%d/%m/%y"))
+        }
+        Some(streak) => format!(
+            "{} days ({} – {})",
+            streak.days(),
+            streak.start.format("%d/%m/%y"),
+            streak.end.format("%d/%m/%y")
+        ),
+        None => "–".to_string(),
+    }
+}
+
+/// Card with the listening streaks of the selected period, the streak running at the end of the
+/// history, and the longest streak of an artist typed in by the user.
+#[function_component]
+pub fn StreaksCard() -> Html {
+    let data_context = use_context::<DataContext>().unwrap();
+    let period = use_context::<PeriodContext>().unwrap();
+    let content = use_context::<ContentContext>().unwrap();
+    let timezone = use_context::<TimezoneContext>().unwrap();
+    let artist: UseStateHandle<Option<String>> = use_state(|| None);
+    let stats: UseStateHandle<Option<StreakStats>> = use_state(|| None);
+    let worker = use_data_worker({
+        let stats = stats.clone();
+        move |output| {
+            if let WorkerOutput::Streaks(result) = output {
+                stats.set(Some(result));
+            }
+        }
+    });
+
+    let deps = (
+        *period,
+        *content,
+        *timezone,
+        (*artist).clone(),
+        data_context.restoring,
+        data_context.summary.revision,
+    );
+    use_effect_with(
+        deps,
+        move |(period, content, timezone, artist, restoring, _)| {
+            if !*restoring {
+                worker.send(WorkerInput::Streaks {
+                    period: *period,
+                    content: *content,
+                    artist: artist.clone(),
+                    timezone: *timezone,
+                });
+            }
+        },
+    );
+
+    let on_artist_change = {
+        let artist = artist.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value().trim().to_string();
+            artist.set(Some(value).filter(|value| !value.is_empty()));
+        })
+    };
+
+    html! {
+        <div class="card shadow mt-8">
+            <div class="card-body items-center">
+                <h2 class="card-title text-2xl"> { "Streaks" } </h2>
+                if let Some(stats) = &*stats {
+                    <div class="stats stats-vertical lg:stats-horizontal">
+                        <div class="stat">
+                            <div class="stat-title"> { "Longest streak" } </div>
+                            <div class="stat-value text-xl"> { format_streak(&stats.longest) } </div>
+                        </div>
+                        <div class="stat">
+                            <div class="stat-title"> { "Current streak" } </div>
+                            <div class="stat-value text-xl"> { format_streak(&stats.current) } </div>
+                            <div class="stat-desc"> { "Up to the last day of your history" } </div>
+                        </div>
+                        <div class="stat">
+                            <div class="stat-title"> { "Longest artist streak" } </div>
+                            if let Some((name, streak)) = &stats.longest_artist {
+                                <div class="stat-value text-xl"> { name } </div>
+                                <div class="stat-desc"> { format_streak(&Some(*streak)) } </div>
+                            } else {
+                                <div class="stat-value text-xl"> { "–" } </div>
+                            }
+                        </div>
+                        <div class="stat">
+                            <div class="stat-title"> { "Days without listening" } </div>
+                            <div class="stat-value text-xl"> { stats.days_without_listening } </div>
+                        </div>
+                    </div>
+                    <label class="label gap-2">
+                        <span class="text-gray-400"> { "How many days in a row did I play" } </span>
+                        <input type="text" class="input input-bordered" placeholder="Artist" onchange={on_artist_change} />
+                    </label>
+                    if artist.is_some() {
+                        <p class="text-lg"> { format_streak(&stats.artist) } </p>
+                    }
+                } else {
+                    <span class="loading loading-dots loading-lg"></span>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,61 @@
+use chrono_tz::Tz;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::data_parser::timezone::Timezone;
+
+use super::data_context::{browser_timezone, TimezoneContext};
+
+/// Offsets to UTC (in hours) that can be picked by hand.
+const OFFSETS: std::ops::RangeInclusive<i32> = -12..=14;
+
+/// Selects the timezone the hours, days and months of the charts are computed in.
+#[function_component]
+pub fn TimezoneSelector() -> Html {
+    let timezone = use_context::<TimezoneContext>().unwrap();
+    let browser = browser_timezone();
+    // Streams from a country with several timezones are shown in the one of the browser.
+    let country = Timezone::Country(match browser {
+        Timezone::Named(timezone) => timezone,
+        _ => Tz::UTC,
+    });
+    let mut options = vec![
+        (format!("Browser ({})", browser), browser),
+        (Timezone::Utc.to_string(), Timezone::Utc),
+        (country.to_string(), country),
+    ];
+    options.extend(
+        OFFSETS
+            .map(|hours| Timezone::Offset(hours * 60))
+            .filter(|offset| *offset != browser)
+            .map(|offset| (offset.to_string(), offset)),
+    );
+
+    let on_select = {
+        let timezone = timezone.clone();
+        let options = options.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some((_, selected)) = select
+                .value()
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| options.get(i))
+            {
+                timezone.set(*selected);
+            }
+        })
+    };
+
+    html! {
+        <div class="flex flex-row items-center justify-center gap-4 mb-8">
+            <select class="select select-bordered text-xl" onchange={on_select}>
+                { for options.iter().enumerate().map(|(i, (label, option))| html! {
+                    <option value={i.to_string()} selected={*timezone == *option}>
+                        { label }
+                    </option>
+                }) }
+            </select>
+        </div>
+    }
+}
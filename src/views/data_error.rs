@@ -1,7 +1,10 @@
 use yew::prelude::*;
 
+use super::components::data_context::DataContext;
+
 #[function_component(DataError)]
 pub fn data_error() -> Html {
+    let data_context = use_context::<DataContext>().unwrap();
     html! {
     <div class="upload-guide-container text-xl">
                        <div class="header flex items-center">
@@ -15,6 +18,10 @@ pub fn data_error() -> Html {
         </div>
 
         <p class="text-xl"> {"We experienced an error while parsing your data. Please ensure you are providing the correct data. If this issue persists do let us know"} </p>
+        if let Some(error) = &data_context.error {
+            <p class="text-xl mt-8"> {"The following problem was found:"} </p>
+            <p class="text-xl text-primary font-mono mt-2"> { error.to_string() } </p>
+        }
                     </div>
         }
 }
@@ -1,12 +1,16 @@
 use std::fmt::Result;
 use std::thread;
 
-use crate::data_parser::playback_record::PlaybackRecord;
-use crate::data_parser::processing::filter_by;
+use crate::data_parser::history_file::HistoryFile;
+use crate::data_parser::report::ImportReport;
+use crate::worker::{ImportId, WorkerInput, WorkerOutput};
 use crate::Route;
 
-use super::components::data_context::DataContext;
+use super::components::data_context::{use_data_worker, DataContext, DataEvent};
 use super::components::file_upload::FileInput;
+use super::components::forget_data::ForgetDataButton;
+use super::components::import_summary::ImportSummary;
+use super::components::progress_bar::ProgressBar;
 use chrono::format;
 use chrono::prelude::*;
 use gloo::console::log;
@@ -17,55 +21,88 @@ use yew::prelude::*;
 use yew_router::hooks::use_navigator;
 use yew_router::navigator;
 
-#[derive(PartialEq, Debug)]
+/// How much of a selected file has been read.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FileProgress {
+    pub name: String,
+    /// Number of bytes read so far.
+    pub loaded: u64,
+    pub size: u64,
+}
+
+impl FileProgress {
+    pub fn percentage(&self) -> u64 {
+        if self.size == 0 {
+            100
+        } else {
+            self.loaded * 100 / self.size
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum FileState {
     NotUploaded,
-    Uploading,
-    Processing,
+    /// The selected files are being read.
+    Uploading {
+        files: Vec<FileProgress>,
+    },
+    /// The records are being parsed, `total` is an estimate made before parsing starts.
+    Processing {
+        records: usize,
+        total: usize,
+    },
     Processed,
 }
 
 #[function_component]
 pub fn FileUploadView() -> Html {
-    let file_contents: UseStateHandle<Vec<Vec<u8>>> = use_state(|| Vec::<Vec<u8>>::new());
     let file_state = use_state(|| FileState::NotUploaded);
-    let file_ammount = use_state(|| 0);
+    let import_report = use_state(ImportReport::default);
     let data_context = use_context::<DataContext>().unwrap();
     let navigator = use_navigator().unwrap();
-    use_effect_with(file_state.clone(), {
+    // The last import that was started, `None` once it is cancelled or done. The messages of
+    // other imports are ignored.
+    let active_import = use_mut_ref(|| None::<ImportId>);
+    let imports_started = use_mut_ref(|| 0 as ImportId);
+    let worker = use_data_worker({
         let file_state = file_state.clone();
         let data_context = data_context.clone();
-        let file_contents = (*file_contents).clone();
         let navigator = navigator.clone();
-        move |_| {
-            if *file_state != FileState::Processing {
-                return;
+        let import_report = import_report.clone();
+        let active_import = active_import.clone();
+        move |output| match output {
+            WorkerOutput::Progress { id, .. } | WorkerOutput::Imported { id, .. }
+                if *active_import.borrow() != Some(id) => {}
+            WorkerOutput::Progress { records, total, .. } => {
+                file_state.set(FileState::Processing { records, total })
             }
-
-            let parsed_data = PlaybackRecord::from_jsons(&file_contents);
-
-            match parsed_data {
-                Ok(data) => {
-                    data_context.dispatch(filter_by(&data, |record| {
-                        record.ts.date_naive()
-                            >= Utc::now()
-                                .with_year(Utc::now().year() - 1)
-                                .unwrap()
-                                .date_naive()
-                    }));
-                    file_state.set(FileState::Processed);
+            WorkerOutput::Imported { result, .. } => {
+                *active_import.borrow_mut() = None;
+                match result {
+                    Ok(report) if report.imported == 0 && report.skipped() > 0 => {
+                        let error = report.rejected[0].clone();
+                        data_context.dispatch(DataEvent::Failed(error));
+                        navigator.push(&Route::DataError);
+                    }
+                    Ok(report) => {
+                        import_report.set(report);
+                        file_state.set(FileState::Processed);
+                    }
+                    Err(e) => {
+                        data_context.dispatch(DataEvent::Failed(e));
+                        navigator.push(&Route::DataError);
+                    }
                 }
-                Err(e) => navigator.push(&Route::DataError),
             }
+            WorkerOutput::Dataset(_)
+            | WorkerOutput::Chart(_)
+            | WorkerOutput::Streaks(_)
+            | WorkerOutput::RankHistory(_)
+            | WorkerOutput::Calendar(_)
+            | WorkerOutput::DayPlays(_) => {}
         }
     });
-    let on_file_ammount = {
-        let file_ammount = file_ammount.clone();
-        Callback::from(move |n| {
-            file_ammount.set(n);
-            log!(format!("New file ammount set: {}", n))
-        })
-    };
     let on_finish_loading = {
         let loading = file_state.clone();
         Callback::from(move |state: FileState| {
@@ -77,20 +114,29 @@ pub fn FileUploadView() -> Html {
         let navigator = navigator.clone();
         Callback::from(move |_| navigator.push(&Route::StatsHome))
     };
-    let on_state_change = {
-        let file_contents = file_contents.clone();
-        let file_ammount = file_ammount.clone();
+    let on_submit = {
         let file_state = file_state.clone();
-        Callback::from(move |records: Vec<u8>| {
-            let mut new_contents = (*file_contents).clone();
-            new_contents.push(records);
-            let current_ammount = new_contents.len();
-
-            file_contents.set(new_contents);
-
-            if *file_ammount <= current_ammount {
-                file_state.set(FileState::Processing);
+        let active_import = active_import.clone();
+        let worker = worker.clone();
+        Callback::from(move |files: Vec<HistoryFile>| {
+            log!(format!("Processing {} files", files.len()));
+            file_state.set(FileState::Processing {
+                records: 0,
+                total: 0,
+            });
+            let id = *imports_started.borrow();
+            *imports_started.borrow_mut() += 1;
+            *active_import.borrow_mut() = Some(id);
+            worker.send(WorkerInput::Import { id, files });
+        })
+    };
+    let on_cancel = {
+        let file_state = file_state.clone();
+        Callback::from(move |_| {
+            if let Some(id) = active_import.borrow_mut().take() {
+                worker.send(WorkerInput::CancelImport(id));
             }
+            file_state.set(FileState::NotUploaded);
         })
     };
     html! {
@@ -103,12 +149,28 @@ pub fn FileUploadView() -> Html {
         <div class="flex flex-col items-center justify-center">
             if *file_state == FileState::Processed {
                <button onclick={on_button_click} class="mbtn text-4xl font-semibold  py-3 h-16"> {"Get ReWrapped!"}</button>
-            } else if *file_state == FileState::Processing {
+               <ImportSummary report={(*import_report).clone()}/>
+            } else if let FileState::Processing { records, total } = *file_state {
                 <div class="flex items-center justify-center w-1/2 text-xl font-semibold py-3 mbtn">{"Proccessing..."}</div>
-                <p class="text-base text-gray-400 mt-8 hover:underline text-center">
+                if total > 0 {
+                    <ProgressBar done={records as u64} total={total as u64} unit="records"/>
+                } else {
+                    <p class="text-base text-gray-400 mt-8 hover:underline text-center">
                         { "This may take a while..." }
-                </p>
-            } else {<FileInput on_file_loaded={on_state_change} on_change_upload_state={on_finish_loading} on_file_ammount= {on_file_ammount}/>
+                    </p>
+                }
+                <button onclick={on_cancel} class="text-base text-gray-400 hover:underline mt-4">
+                    { "Cancel" }
+                </button>
+            } else if data_context.restoring {
+                <span class="loading loading-dots loading-lg"></span>
+            } else {
+                if !data_context.summary.is_empty() && *file_state == FileState::NotUploaded {
+                    <button onclick={on_button_click.clone()} class="mbtn text-2xl font-semibold py-3 h-16 mb-2"> {"Continue with saved data"}</button>
+                    <ForgetDataButton/>
+                    <p class="text-base text-gray-400 my-8"> { "or add more files to it:" } </p>
+                }
+                <FileInput on_submit={on_submit} on_change_upload_state={on_finish_loading}/>
                 <p class="text-base text-gray-400 mt-8 hover:underline text-center">
                     <a href="/upload-guide" >
                         { "What do I need to upload?" }
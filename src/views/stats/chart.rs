@@ -3,6 +3,7 @@ use std::{str::FromStr, thread};
 
 use crate::data_parser::playback_record::PlaybackRecord;
 use crate::views::components::charts::bar_chart::{BarChart, BarChartData};
+use crate::views::components::charts::heatmap_chart::{HeatmapChart, HeatmapChartData};
 use crate::views::components::charts::polar_area_chart::{PolarAreaChart, PolarAreaChartData};
 use crate::{
     views::components::{
@@ -15,7 +16,21 @@ use gloo::{console::log, timers::future::sleep};
 use yew::prelude::*;
 use yew_router::{hooks::use_navigator, navigator};
 
-use crate::{data_parser::queries, views::components::data_context::DataContext};
+use crate::{
+    data_parser::{
+        chart::ChartType, comparison::Comparison, content::ContentFilter, metric::Metric,
+        period::Period, queries::QueryOptions, report::with_thousands_separator,
+        sessions::DEFAULT_SESSION_GAP_MINUTES, skips::MIN_PLAYS, track::TrackGrouping,
+    },
+    views::components::{
+        comparison_table::ComparisonTable,
+        data_context::{
+            use_data_worker, ContentContext, DataContext, PeriodContext, TimezoneContext,
+        },
+    },
+    worker::{WorkerInput, WorkerOutput},
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 
 pub fn get_gradient(start_color: &str, end_color: &str, steps: usize) -> Vec<String> {
     let start_r = u8::from_str_radix(&start_color[1..3], 16).unwrap();
@@ -35,38 +50,44 @@ pub fn get_gradient(start_color: &str, end_color: &str, steps: usize) -> Vec<Str
         .collect()
 }
 
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub enum ChartType {
-    Artists,
-    Songs,
-    Albums,
-    Week,
-    Months,
-    Days,
-    Day,
-    Platform,
-    Country,
-}
-
 impl ChartType {
-    fn get_chart(&self, dt: Vec<(String, f32)>) -> Html {
+    fn get_chart(&self, dt: Vec<(String, f32)>, id: &str) -> Html {
+        let id = format!("{}-{}", self.to_string().to_lowercase(), id);
         match self {
-            Self::Artists | Self::Songs | Self::Albums | Self::Days => {
-                html! {<PieChart data={PieChartData::convert(dt)}/>}
+            Self::Artists
+            | Self::Songs
+            | Self::Albums
+            | Self::Days
+            | Self::Shows
+            | Self::Episodes
+            | Self::NewMusic => {
+                html! {<PieChart data={PieChartData::convert(dt)} id={id}/>}
             }
 
-            Self::Months | Self::Week | Self::Platform | Self::Country => {
-                html! {<BarChart data={BarChartData::convert(dt)}/>}
+            Self::Months
+            | Self::Week
+            | Self::Platform
+            | Self::Country
+            | Self::PodcastMonths
+            | Self::Skips
+            | Self::Sessions
+            | Self::SessionStarts
+            | Self::NewArtists
+            | Self::Discoveries => {
+                html! {<BarChart data={BarChartData::convert(dt)} id={id}/>}
             }
             Self::Day => {
-                html! {<PolarAreaChart data={PolarAreaChartData::convert(dt)}/>}
+                html! {<PolarAreaChart data={PolarAreaChartData::convert(dt)} id={id}/>}
+            }
+            Self::Heatmap => {
+                html! {<HeatmapChart data={HeatmapChartData::convert(dt)} id={id}/>}
             }
         }
     }
     fn get_message(&self) -> String {
         match self {
             Self::Artists | Self::Songs | Self::Albums => format!(
-                "How much of your favorite {} occupied your year?",
+                "How much of your favorite {} occupied your listening?",
                 self.to_string().to_lowercase()
             ),
             Self::Week => format!("Which day of the week did you listen to music the most?"),
@@ -74,7 +95,22 @@ impl ChartType {
             Self::Day => format!("Which hour of the day did you listen to music the most?"),
             Self::Country => format!("From where did you listen to music the most?"),
             Self::Platform => format!("Which platform did you use to listen to music the most?"),
-            Self::Days => format!("Which days did you listen to music the most this year?"),
+            Self::Days => format!("Which days did you listen to music the most?"),
+            Self::Shows => format!("Which podcasts did you listen to the most?"),
+            Self::Episodes => format!("Which episodes did you listen to the most?"),
+            Self::PodcastMonths => format!("How many hours of podcasts did you listen to?"),
+            Self::Skips => format!(
+                "Which songs did you skip the most? (% of plays, songs played at least {} times)",
+                MIN_PLAYS
+            ),
+            Self::Sessions => format!("How long do your listening sessions last?"),
+            Self::SessionStarts => format!("When do you start listening?"),
+            Self::NewArtists => format!("How many new artists did you discover every month?"),
+            Self::Discoveries => {
+                format!("Which new artists did you listen to the most? (minutes)")
+            }
+            Self::NewMusic => format!("How much of your listening went to music new to you?"),
+            Self::Heatmap => format!("At which hours of which days did you listen the most?"),
         }
     }
     fn get_title(&self) -> String {
@@ -86,46 +122,19 @@ impl ChartType {
             Self::Day => format!("Hour of the day"),
             Self::Country => format!("Countries"),
             Self::Platform => format!("Platforms"),
+            Self::Shows => format!("Top Podcasts"),
+            Self::Episodes => format!("Top Episodes"),
+            Self::PodcastMonths => format!("Podcast hours per month"),
+            Self::Skips => format!("Most skipped"),
+            Self::Sessions => format!("Session length"),
+            Self::SessionStarts => format!("Session starts"),
+            Self::NewArtists => format!("New artists"),
+            Self::Discoveries => format!("Top discoveries"),
+            Self::NewMusic => format!("New vs known music"),
+            Self::Heatmap => format!("Day and hour"),
         }
     }
 }
-impl std::str::FromStr for ChartType {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.trim().to_lowercase().as_str() {
-            "artist" | "artists" => Ok(Self::Artists),
-            "song" | "songs" => Ok(Self::Songs),
-            "album" | "albums" => Ok(Self::Albums),
-            "week" | "weeks" => Ok(Self::Week),
-            "months" | "month" => Ok(Self::Months),
-            "day" => Ok(Self::Day),
-            "days" => Ok(Self::Days),
-            "platform" | "platforms" => Ok(Self::Platform),
-            "country" | "countries" => Ok(Self::Country),
-            _ => Err(()),
-        }
-    }
-}
-impl std::fmt::Display for ChartType {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Artists => "Artists".to_string(),
-                Self::Songs => "Songs".to_string(),
-                Self::Albums => "Albums".to_string(),
-                Self::Week => "Week".to_string(),
-                Self::Months => "Months".to_string(),
-                Self::Days => "Days".to_string(),
-                Self::Platform => "Platform".to_string(),
-                Self::Country => "Country".to_string(),
-                Self::Day => "Day".to_string(),
-            }
-        )
-    }
-}
 fn from_raw_to_pie_data(data: Vec<(String, f32)>) -> Vec<PieChartData> {
     let colours = [
         "#91eeb2", "#65e793", "#39e074", "#1fc65a", "#189a46", "#116e32",
@@ -148,34 +157,128 @@ pub struct ChartViewProps {
 #[function_component(ChartView)]
 pub fn chart_view(props: &ChartViewProps) -> Html {
     let data_context = use_context::<DataContext>().unwrap();
+    let period = use_context::<PeriodContext>().unwrap();
+    let content = use_context::<ContentContext>().unwrap();
+    let timezone = use_context::<TimezoneContext>().unwrap();
+    let compare_with: UseStateHandle<Option<Period>> = use_state(|| None);
+    let tracks = use_state(TrackGrouping::default);
+    let metric = use_state(Metric::default);
+    let session_gap_minutes = use_state(|| DEFAULT_SESSION_GAP_MINUTES);
+    let normalize = use_state(|| false);
     let data: UseStateHandle<Vec<(String, f32)>> = use_state(|| vec![]);
+    let previous_data: UseStateHandle<Vec<(String, f32)>> = use_state(|| vec![]);
+    let comparison: UseStateHandle<Option<Comparison>> = use_state(|| None);
+    let summary: UseStateHandle<Vec<(String, String)>> = use_state(|| vec![]);
     let navigator = use_navigator().unwrap();
     let loading = use_state(|| true);
-
-    use_effect_with((), {
+    let available =
+        !props.chart_type.needs_extended_fields() || data_context.summary.has_extended_fields();
+    let supported = props.chart_type.supports_period(&period);
+    // Charts that need extended fields leave out the records of the account data export.
+    let partial =
+        props.chart_type.needs_extended_fields() && data_context.summary.has_mixed_schemas();
+    let years = data_context.summary.years.clone();
+    let worker = use_data_worker({
         let data = data.clone();
+        let previous_data = previous_data.clone();
+        let comparison = comparison.clone();
+        let summary = summary.clone();
+        let loading = loading.clone();
+        move |output| {
+            if let WorkerOutput::Chart(result) = output {
+                data.set(result.data);
+                previous_data.set(result.previous_data);
+                comparison.set(result.comparison);
+                summary.set(result.summary);
+                loading.set(false);
+            }
+        }
+    });
+
+    let options = QueryOptions {
+        content: *content,
+        tracks: *tracks,
+        metric: *metric,
+        session_gap_minutes: *session_gap_minutes,
+        normalize: *normalize,
+        timezone: *timezone,
+    };
+    let deps = (
+        *period,
+        *compare_with,
+        options,
+        data_context.restoring,
+        data_context.summary.revision,
+    );
+    use_effect_with(deps, {
         let loading = loading.clone();
         let chart_type = props.chart_type.clone();
-        move |_| {
-            if data_context.inner.is_empty() {
+        let data_context = data_context.clone();
+        move |(period, compare_with, options, restoring, _)| {
+            if *restoring {
+                return;
+            }
+            if data_context.summary.is_empty() {
                 navigator.push(&Route::Upload);
                 return;
             }
-            let dt = &data_context.inner;
-            data.set(match chart_type {
-                ChartType::Albums => queries::get_top_albums_percentages(dt, 4.0, 5),
-                ChartType::Artists => queries::get_top_artists_percentages(dt, 4.0, 5),
-                ChartType::Songs => queries::get_top_songs_percentages(dt, 4.0, 5),
-                ChartType::Week => queries::get_day_distribution(dt),
-                ChartType::Months => queries::get_months_distribution(dt),
-                ChartType::Platform => queries::get_top_platforms(dt),
-                ChartType::Country => queries::get_top_countries(dt),
-                ChartType::Days => queries::get_top_days(dt, 5),
-                ChartType::Day => queries::get_hours_of_the_day_distribution(dt),
+            loading.set(true);
+            worker.send(WorkerInput::Query {
+                chart_type,
+                period: *period,
+                compare_with: *compare_with,
+                options: *options,
             });
-            loading.set(false);
         }
     });
+    let on_compare_select = {
+        let compare_with = compare_with.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            compare_with.set(select.value().parse().ok().map(Period::Year));
+        })
+    };
+    let on_metric_select = {
+        let metric = metric.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(selected) = select
+                .value()
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| Metric::ALL.get(i))
+            {
+                metric.set(*selected);
+            }
+        })
+    };
+    let on_session_gap_change = {
+        let session_gap_minutes = session_gap_minutes.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(minutes) = input.value().parse::<u32>() {
+                session_gap_minutes.set(minutes.max(1));
+            }
+        })
+    };
+    let on_merge_toggle = {
+        let tracks = tracks.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            tracks.set(if input.checked() {
+                TrackGrouping::TitleAndArtist
+            } else {
+                TrackGrouping::Uri
+            });
+        })
+    };
+    let on_normalize_toggle = {
+        let normalize = normalize.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            normalize.set(input.checked());
+        })
+    };
     html! {
     <div class="flex flex-col items-center justify-center h-full">
       <div class="flex flex-row items-center justify-center w-full">
@@ -186,15 +289,105 @@ pub fn chart_view(props: &ChartViewProps) -> Html {
         <p class="text-4xl text-text-base ml-4 text-center w-full">
                    { props.chart_type.get_title() }
         </p>
+        <div class="flex flex-col items-center ml-4 w-1/4">
+            <p class="text-2xl text-gray-400 text-center">
+                       { match props.chart_type.content().unwrap_or(*content) {
+                           ContentFilter::All => period.to_string(),
+                           content => format!("{} · {}", *period, content),
+                       } }
+            </p>
+            <select class="select select-bordered mt-2" onchange={on_compare_select}>
+                <option value="none" selected={compare_with.is_none()}> { "No comparison" } </option>
+                { for years.iter().map(|year| html! {
+                    <option value={year.to_string()} selected={*compare_with == Some(Period::Year(*year))}>
+                        { format!("Compare with {}", year) }
+                    </option>
+                }) }
+            </select>
+            if props.chart_type.supports_metric() {
+                <select class="select select-bordered mt-2" onchange={on_metric_select}>
+                    { for Metric::ALL.iter().enumerate().map(|(i, m)| html! {
+                        <option value={i.to_string()} selected={*metric == *m}> { m.to_string() } </option>
+                    }) }
+                </select>
+            }
+            if props.chart_type.uses_sessions() {
+                <label class="label gap-2 mt-2">
+                    <span class="text-gray-400"> { "New session after a break of" } </span>
+                    <input type="number" min="1" class="input input-bordered w-20" value={session_gap_minutes.to_string()} onchange={on_session_gap_change} />
+                    <span class="text-gray-400"> { "min" } </span>
+                </label>
+            }
+            if props.chart_type == ChartType::Songs {
+                <label class="label cursor-pointer gap-2 mt-2">
+                    <span class="text-gray-400"> { "Merge releases of the same song" } </span>
+                    <input type="checkbox" class="checkbox" checked={*tracks == TrackGrouping::TitleAndArtist} onchange={on_merge_toggle} />
+                </label>
+            }
+            if props.chart_type == ChartType::Heatmap {
+                <label class="label cursor-pointer gap-2 mt-2">
+                    <span class="text-gray-400"> { "Show shares of the listening time" } </span>
+                    <input type="checkbox" class="checkbox" checked={*normalize} onchange={on_normalize_toggle} />
+                </label>
+            }
+        </div>
       </div>
 
       <div class="w-full  h-full flex flex-col items-center">
         <h3 class="text-xl font-medium mb-4 text-gray-700"> {props.chart_type.get_message()  } </h3>
-            if *loading {
+            if partial {
+                <p class="text-base text-center text-gray-400 mb-4">
+                    { format!(
+                        "Computed over the {} streams of your extended streaming history only, the account data export does not record this.",
+                        with_thousands_separator(data_context.summary.extended_records)
+                    ) }
+                </p>
+            }
+            if !available {
+                <p class="text-xl text-center text-gray-400">
+                    { "This chart is unavailable for the account data export. Upload your extended streaming history to see it." }
+                </p>
+            }
+            else if !supported {
+                <p class="text-xl text-center text-gray-400">
+                    { "Over all time, everything you listened to was new once. Pick a year or a range of days to see what you discovered in it." }
+                </p>
+            }
+            else if *loading {
                 <span class="loading loading-dots loading-lg"></span>
             }
+            else if let Some(previous) = *compare_with {
+                <div class="flex flex-row w-full h-full">
+                    <div class="flex flex-col items-center w-1/2 h-full">
+                        <p class="text-2xl"> { period.to_string() } </p>
+                        {props.chart_type.get_chart((*data).clone(), "current")}
+                    </div>
+                    <div class="flex flex-col items-center w-1/2 h-full">
+                        <p class="text-2xl"> { previous.to_string() } </p>
+                        {props.chart_type.get_chart((*previous_data).clone(), "previous")}
+                    </div>
+                </div>
+                if let Some(comparison) = (*comparison).clone() {
+                    <ComparisonTable comparison={comparison} period={*period} previous={previous}/>
+                }
+            }
+            else if data.is_empty() {
+                <p class="text-xl text-center text-gray-400">
+                    { "You did not listen to anything during this period." }
+                </p>
+            }
             else {
-                {props.chart_type.get_chart((*data).clone())}
+                {props.chart_type.get_chart((*data).clone(), "current")}
+            }
+            if available && supported && !*loading && !summary.is_empty() {
+                <div class="stats shadow mt-8">
+                    { for summary.iter().map(|(title, value)| html! {
+                        <div class="stat">
+                            <div class="stat-title"> { title } </div>
+                            <div class="stat-value text-2xl"> { value } </div>
+                        </div>
+                    }) }
+                </div>
             }
         </div>
     </div>
@@ -1,14 +1,25 @@
-use chart::ChartType;
 use yew::prelude::*;
 use yew_router::{hooks::use_navigator, navigator};
 
-use crate::Route;
+use crate::{
+    data_parser::chart::ChartType,
+    views::components::{
+        calendar_card::CalendarCard, content_selector::ContentSelector, data_context::DataContext,
+        data_sources::DataSources, forget_data::ForgetDataButton, period_selector::PeriodSelector,
+        streaks_card::StreaksCard, timezone_selector::TimezoneSelector,
+    },
+    Route,
+};
 
 pub mod chart;
+pub mod rank_history;
 
 #[function_component(StatsHome)]
 pub fn stats_home() -> Html {
     let navigator = use_navigator().unwrap();
+    let data_context = use_context::<DataContext>().unwrap();
+    let has_extended_fields = data_context.summary.has_extended_fields();
+    let has_podcasts = data_context.summary.has_podcasts;
 
     // Define a collection of chart types and their respective labels
     let chart_buttons = vec![
@@ -21,6 +32,16 @@ pub fn stats_home() -> Html {
         (ChartType::Country, "Country"),
         (ChartType::Days, "Days"),
         (ChartType::Day, "Day"),
+        (ChartType::Heatmap, "Day and hour"),
+        (ChartType::Skips, "Most skipped"),
+        (ChartType::Sessions, "Sessions"),
+        (ChartType::SessionStarts, "Session starts"),
+        (ChartType::NewArtists, "New artists"),
+        (ChartType::Discoveries, "Discoveries"),
+        (ChartType::NewMusic, "New music"),
+        (ChartType::Shows, "Podcasts"),
+        (ChartType::Episodes, "Episodes"),
+        (ChartType::PodcastMonths, "Podcast hours"),
     ];
 
     html! {
@@ -34,9 +55,15 @@ pub fn stats_home() -> Html {
                 <h1 class="text-4xl text-center"> { "Statistics" } </h1>
             </div>
         </div>
+            <PeriodSelector/>
+            <ContentSelector/>
+            <TimezoneSelector/>
             <div class="grid  gap-4 sm:grid-cols-2 sm:auto-rows-fr">
                 // Generate buttons dynamically using a map and a loop
-                {for chart_buttons.into_iter().map(|(chart_type, label)| {
+                {for chart_buttons.into_iter()
+                    .filter(|(chart_type, _)| has_extended_fields || !chart_type.needs_extended_fields())
+                    .filter(|(chart_type, _)| has_podcasts || chart_type.content().is_none())
+                    .map(|(chart_type, label)| {
                     let navigator = navigator.clone();
                     html! {
                         <button
@@ -49,6 +76,21 @@ pub fn stats_home() -> Html {
                         </button>
                     }
                 })}
+                <button
+                    class="mbtn text-2xl font-semibold  h-16 w-40"
+                    onclick={
+                        let navigator = navigator.clone();
+                        Callback::from(move |_| navigator.push(&Route::RankHistory))
+                    }
+                >
+                    { "Rank over time" }
+                </button>
+            </div>
+            <StreaksCard/>
+            <CalendarCard/>
+            <DataSources/>
+            <div class="flex justify-center mt-8">
+                <ForgetDataButton/>
             </div>
         </div>
     }
@@ -0,0 +1,167 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+use crate::{
+    data_parser::{
+        content::ContentFilter,
+        metric::Metric,
+        queries::QueryOptions,
+        rank_history::{RankInterval, RankWindow},
+    },
+    views::components::{
+        charts::rank_chart::{RankChart, RankChartData},
+        data_context::{
+            use_data_worker, ContentContext, DataContext, PeriodContext, TimezoneContext,
+        },
+    },
+    worker::{WorkerInput, WorkerOutput},
+    Route,
+};
+
+/// Returns the item of `all` whose index is the value of the select that fired `e`.
+fn selected<T: Copy>(e: &Event, all: &[T]) -> Option<T> {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    select
+        .value()
+        .parse::<usize>()
+        .ok()
+        .and_then(|i| all.get(i))
+        .copied()
+}
+
+/// How the ranking of the top artists of the selected period evolved, week by week or month by
+/// month.
+#[function_component(RankHistoryView)]
+pub fn rank_history_view() -> Html {
+    let data_context = use_context::<DataContext>().unwrap();
+    let period = use_context::<PeriodContext>().unwrap();
+    let content = use_context::<ContentContext>().unwrap();
+    let timezone = use_context::<TimezoneContext>().unwrap();
+    let interval = use_state(RankInterval::default);
+    let window = use_state(RankWindow::default);
+    let metric = use_state(Metric::default);
+    let data: UseStateHandle<RankChartData> = use_state(RankChartData::default);
+    let navigator = use_navigator().unwrap();
+    let loading = use_state(|| true);
+    let worker = use_data_worker({
+        let data = data.clone();
+        let loading = loading.clone();
+        move |output| {
+            if let WorkerOutput::RankHistory(history) = output {
+                data.set(RankChartData::convert(history));
+                loading.set(false);
+            }
+        }
+    });
+
+    let options = QueryOptions {
+        content: *content,
+        metric: *metric,
+        timezone: *timezone,
+        ..Default::default()
+    };
+    let deps = (
+        *period,
+        options,
+        *interval,
+        *window,
+        data_context.restoring,
+        data_context.summary.revision,
+    );
+    use_effect_with(deps, {
+        let loading = loading.clone();
+        let data_context = data_context.clone();
+        move |(period, options, interval, window, restoring, _)| {
+            if *restoring {
+                return;
+            }
+            if data_context.summary.is_empty() {
+                navigator.push(&Route::Upload);
+                return;
+            }
+            loading.set(true);
+            worker.send(WorkerInput::RankHistory {
+                period: *period,
+                options: *options,
+                interval: *interval,
+                window: *window,
+            });
+        }
+    });
+    let on_interval_select = {
+        let interval = interval.clone();
+        Callback::from(move |e: Event| {
+            if let Some(selected) = selected(&e, &RankInterval::ALL) {
+                interval.set(selected);
+            }
+        })
+    };
+    let on_window_select = {
+        let window = window.clone();
+        Callback::from(move |e: Event| {
+            if let Some(selected) = selected(&e, &RankWindow::ALL) {
+                window.set(selected);
+            }
+        })
+    };
+    let on_metric_select = {
+        let metric = metric.clone();
+        Callback::from(move |e: Event| {
+            if let Some(selected) = selected(&e, &Metric::ALL) {
+                metric.set(selected);
+            }
+        })
+    };
+    html! {
+    <div class="flex flex-col items-center justify-center h-full">
+      <div class="flex flex-row items-center justify-center w-full">
+        <a class="logo-container w-1/4 flex flex-col items-center mb-10" href="/">
+          <img src="/assets/logo.svg" alt="logo" class="logo mb-4 w-60" />
+          <h2 class="text-4xl text-center"> { "ReWrapped" } </h2>
+        </a>
+        <p class="text-4xl text-text-base ml-4 text-center w-full">
+                   { "Rank over time" }
+        </p>
+        <div class="flex flex-col items-center ml-4 w-1/4">
+            <p class="text-2xl text-gray-400 text-center">
+                       { match *content {
+                           ContentFilter::All => period.to_string(),
+                           content => format!("{} · {}", *period, content),
+                       } }
+            </p>
+            <select class="select select-bordered mt-2" onchange={on_interval_select}>
+                { for RankInterval::ALL.iter().enumerate().map(|(i, value)| html! {
+                    <option value={i.to_string()} selected={*interval == *value}> { value.to_string() } </option>
+                }) }
+            </select>
+            <select class="select select-bordered mt-2" onchange={on_window_select}>
+                { for RankWindow::ALL.iter().enumerate().map(|(i, value)| html! {
+                    <option value={i.to_string()} selected={*window == *value}> { value.to_string() } </option>
+                }) }
+            </select>
+            <select class="select select-bordered mt-2" onchange={on_metric_select}>
+                { for Metric::ALL.iter().enumerate().map(|(i, value)| html! {
+                    <option value={i.to_string()} selected={*metric == *value}> { value.to_string() } </option>
+                }) }
+            </select>
+        </div>
+      </div>
+
+      <div class="w-full  h-full flex flex-col items-center">
+        <h3 class="text-xl font-medium mb-4 text-gray-700"> { "How your top artists climbed and fell in your ranking" } </h3>
+            if *loading {
+                <span class="loading loading-dots loading-lg"></span>
+            }
+            else if data.series.is_empty() {
+                <p class="text-xl text-center text-gray-400">
+                    { "You did not listen to anything during this period." }
+                </p>
+            }
+            else {
+                <RankChart data={(*data).clone()} />
+            }
+        </div>
+    </div>
+    }
+}
@@ -48,10 +48,19 @@ pub fn UploadGuide() -> Html {
 
                     <h3 class="text-2xl font-semibold mb-2 mt-4"> { "How to Upload Your User Data" } </h3>
                     <p>
-                        { "After downloading your data from Spotify, unzip the files and upload only those in the following format: " }
+                        { "After downloading your data from Spotify, upload the " }
+                        <span class="text-primary"> { "\"my_spotify_data.zip\"" } </span>
+                        { " archive as it is. If you have already unzipped it, upload only the files in the following format: " }
                         <span class="text-primary"> { "\"Streaming_History_Audio_xxxx_yyyy_z.json\"" } </span>
                         { "." }
                     </p>
+                    <p>
+                        { "The quicker " }
+                        <span class="text-primary"> { "\"Account data\"" } </span>
+                        { " export (" }
+                        <span class="text-primary"> { "\"StreamingHistory_music_x.json\"" } </span>
+                        { ") is supported as well. It only contains the artist, track and duration of every stream, so charts such as platforms and countries are not available for it." }
+                    </p>
 
                     <h3 class="text-2xl font-semibold mb-2 mt-4"> { "An Important Note About Privacy" } </h3>
                     <p>
@@ -72,7 +81,7 @@ pub fn UploadGuide() -> Html {
                         <li> { "- Everything runs locally in your browser through WebAssembly (Wasm)." } </li>
                         <li> { "- This ensures that no part of your data interacts with external servers." } </li>
                         <li> { "- Essentially, all processing happens client-side in your local environment for absolute privacy." } </li>
-                        <li> { "- Even in your device nothing is stored in the browser (to avoid any malicious actor from accessing the browser storage). This is also the reason why reloading makes the app completely reload: Everything is not persistent and a copy exists locally only while the browser window is open." } </li>
+                        <li> { "- To spare you from uploading your files again, the parsed records are kept in your browser's own storage (IndexedDB) on this device only. The \"Forget my data\" button on the statistics page deletes them at any time." } </li>
                     </ul>
 
                 </div>
@@ -0,0 +1,453 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::NaiveDate;
+use gloo::{
+    timers::callback::Timeout,
+    worker::{HandlerId, Worker, WorkerScope},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::data_parser::{
+    archive::expand_archives,
+    calendar::{get_calendar, get_day_plays, CalendarDay, DayPlay},
+    chart::ChartType,
+    comparison::Comparison,
+    content::ContentFilter,
+    dataset::{DataAction, DatasetSummary},
+    error::ParseError,
+    history_file::HistoryFile,
+    period::Period,
+    playback_record::{PlaybackRecord, RecordIdentity},
+    queries::{self, QueryOptions},
+    rank_history::{get_artist_rank_history, RankHistory, RankInterval, RankWindow},
+    report::ImportReport,
+    streaks::{get_streak_stats, StreakStats},
+    streaming::{count_records, ParseMode},
+    timezone::Timezone,
+};
+use crate::storage;
+
+/// Path the worker script is served at, see the `data-type="worker"` link in `index.html`.
+pub const WORKER_PATH: &str = "/worker.js";
+
+/// Number of artists followed by a [`WorkerInput::RankHistory`].
+const RANKED_ARTISTS: usize = 10;
+
+/// Lowest rank shown by a [`WorkerInput::RankHistory`].
+const MAX_SHOWN_RANK: usize = 20;
+
+/// Identifies an import, so that its progress and report are not taken for the ones of an
+/// import that was cancelled.
+pub type ImportId = u32;
+
+/// A request to the [`DataWorker`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum WorkerInput {
+    /// Parses the uploaded files in [`ParseMode::Lenient`] and adds their records to the dataset.
+    /// An import that is still running is cancelled.
+    Import {
+        id: ImportId,
+        files: Vec<HistoryFile>,
+    },
+    /// Stops the import `id` before its next file. The dataset is left untouched.
+    CancelImport(ImportId),
+    /// Applies a change to the dataset.
+    Update(DataAction),
+    /// Asks for a [`WorkerOutput::Dataset`] now and after every change of the dataset, with the
+    /// years of `Timezone`. A later request replaces the timezone.
+    Subscribe(Timezone),
+    /// Runs the query of a chart over the records of `period`, and over the records of
+    /// `compare_with` if it is set.
+    Query {
+        chart_type: ChartType,
+        period: Period,
+        compare_with: Option<Period>,
+        options: QueryOptions,
+    },
+    /// Computes the streaks of the records of `period` whose content passes `content`, and the
+    /// longest streak of `artist` if it is set. Days are those of `timezone`.
+    Streaks {
+        period: Period,
+        content: ContentFilter,
+        artist: Option<String>,
+        timezone: Timezone,
+    },
+    /// Computes the listening of every day of `period` in `timezone`, for the records whose
+    /// content passes `content`.
+    Calendar {
+        period: Period,
+        content: ContentFilter,
+        timezone: Timezone,
+    },
+    /// Lists what was played on `date` in `timezone`, for the records whose content passes
+    /// `content`.
+    DayPlays {
+        date: NaiveDate,
+        content: ContentFilter,
+        timezone: Timezone,
+    },
+    /// Computes how the ranking of the top artists of `period` evolved over that period, see
+    /// [`get_artist_rank_history`].
+    RankHistory {
+        period: Period,
+        options: QueryOptions,
+        interval: RankInterval,
+        window: RankWindow,
+    },
+}
+
+/// The result of a [`WorkerInput::Query`], empty if the chart does not
+/// [support](ChartType::supports_period) the period.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ChartResult {
+    pub data: Vec<(String, f32)>,
+    /// The data of the period compared with, empty if there is none.
+    pub previous_data: Vec<(String, f32)>,
+    pub comparison: Option<Comparison>,
+    /// Key figures of the period, e.g. `("Longest session", "3 h 20 min")`. Empty for most
+    /// charts.
+    pub summary: Vec<(String, String)>,
+}
+
+/// A response of the [`DataWorker`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum WorkerOutput {
+    /// Number of records parsed so far out of the `total` records of the import `id`.
+    Progress {
+        id: ImportId,
+        records: usize,
+        total: usize,
+    },
+    /// The report of the import `id`, whose records were added to the dataset.
+    Imported {
+        id: ImportId,
+        result: Result<ImportReport, ParseError>,
+    },
+    /// The dataset changed, see [`WorkerInput::Subscribe`].
+    Dataset(DatasetSummary),
+    Chart(ChartResult),
+    Streaks(StreakStats),
+    RankHistory(RankHistory),
+    Calendar(Vec<CalendarDay>),
+    DayPlays(Vec<DayPlay>),
+}
+
+/// An internal message of the [`DataWorker`].
+pub enum DataWorkerMessage {
+    /// The dataset was restored from the browser storage.
+    Restored(Vec<PlaybackRecord>),
+    /// Parses the next file of the import in progress.
+    ContinueImport,
+}
+
+/// An import in progress. Its files are parsed one per turn of the event loop of the worker, so
+/// that a [`WorkerInput::CancelImport`] is received between two files.
+struct Import {
+    id: ImportId,
+    /// The bridge that asked for the import.
+    handler: HandlerId,
+    files: VecDeque<HistoryFile>,
+    /// Number of records of all the files, estimated before parsing them.
+    total: usize,
+    /// Number of records parsed from the files done.
+    parsed: usize,
+    done: Vec<(Vec<PlaybackRecord>, ImportReport)>,
+}
+
+/// Web worker that owns the dataset. It restores and stores it in the browser, parses the
+/// uploads and runs the queries off the UI thread.
+pub struct DataWorker {
+    records: Vec<PlaybackRecord>,
+    /// Number of changes made to `records`, see [`DatasetSummary::revision`].
+    revision: usize,
+    /// The requests received while the stored dataset is being restored, `None` once it is.
+    pending: Option<Vec<(WorkerInput, HandlerId)>>,
+    /// The bridges that asked to be told about changes, with the timezone of their summaries.
+    subscribers: HashMap<HandlerId, Timezone>,
+    import: Option<Import>,
+}
+
+impl DataWorker {
+    /// Stores the dataset after a change, unless it was just restored, and sends its summary to
+    /// the subscribers.
+    fn changed(&mut self, scope: &WorkerScope<Self>) {
+        self.revision += 1;
+        if self.revision > 1 {
+            if self.records.is_empty() {
+                storage::forget_records();
+            } else {
+                storage::store_records(&self.records);
+            }
+        }
+        for (id, timezone) in &self.subscribers {
+            self.summarize(scope, *id, *timezone);
+        }
+    }
+
+    fn summarize(&self, scope: &WorkerScope<Self>, id: HandlerId, timezone: Timezone) {
+        let summary = DatasetSummary::new(&self.records, self.revision, timezone);
+        scope.respond(id, WorkerOutput::Dataset(summary));
+    }
+
+    fn start_import(
+        &mut self,
+        scope: &WorkerScope<Self>,
+        handler: HandlerId,
+        id: ImportId,
+        files: Vec<HistoryFile>,
+    ) {
+        let files = match expand_archives(files) {
+            Ok(files) => files,
+            Err(e) => {
+                self.import = None;
+                let result = Err(e);
+                scope.respond(handler, WorkerOutput::Imported { id, result });
+                return;
+            }
+        };
+        self.import = Some(Import {
+            id,
+            handler,
+            total: files.iter().map(|file| count_records(&file.contents)).sum(),
+            files: files.into(),
+            parsed: 0,
+            done: vec![],
+        });
+        Self::continue_import_later(scope);
+    }
+
+    /// Sends [`DataWorkerMessage::ContinueImport`] after the requests that were already received.
+    fn continue_import_later(scope: &WorkerScope<Self>) {
+        let scope = scope.clone();
+        Timeout::new(0, move || {
+            scope.send_message(DataWorkerMessage::ContinueImport)
+        })
+        .forget();
+    }
+
+    /// Parses the next file of the import in progress, or finishes it once every file is parsed.
+    fn continue_import(&mut self, scope: &WorkerScope<Self>) {
+        // The import was cancelled.
+        let Some(import) = &mut self.import else {
+            return;
+        };
+        let Some(file) = import.files.pop_front() else {
+            if let Some(import) = self.import.take() {
+                self.finish_import(scope, import);
+            }
+            return;
+        };
+        let (id, handler, total, parsed) = (import.id, import.handler, import.total, import.parsed);
+        match PlaybackRecord::from_file_with_progress(&file, ParseMode::Lenient, |records| {
+            let records = parsed + records;
+            scope.respond(handler, WorkerOutput::Progress { id, records, total })
+        }) {
+            Ok((records, report)) => {
+                import.parsed += records.len() + report.skipped();
+                import.done.push((records, report));
+                Self::continue_import_later(scope);
+            }
+            Err(e) => {
+                self.import = None;
+                let result = Err(e);
+                scope.respond(handler, WorkerOutput::Imported { id, result });
+            }
+        }
+    }
+
+    /// Adds the records of the files of `import` to the dataset, and sends its report.
+    fn finish_import(&mut self, scope: &WorkerScope<Self>, import: Import) {
+        let (records, report) = PlaybackRecord::combine(import.done);
+        let known: HashSet<RecordIdentity> =
+            self.records.iter().map(PlaybackRecord::identity).collect();
+        let report = report.with_kept(
+            records
+                .iter()
+                .filter(|record| !known.contains(&record.identity())),
+        );
+        drop(known);
+        // The records that are already imported are appended too, so that they get the sources
+        // of these files.
+        if !records.is_empty() {
+            DataAction::Append(records).apply(&mut self.records);
+            self.changed(scope);
+        }
+        let result = Ok(report);
+        scope.respond(
+            import.handler,
+            WorkerOutput::Imported {
+                id: import.id,
+                result,
+            },
+        );
+    }
+
+    fn query(
+        &self,
+        chart_type: ChartType,
+        period: &Period,
+        compare_with: &Option<Period>,
+        options: &QueryOptions,
+    ) -> ChartResult {
+        if !chart_type.supports_period(period) {
+            return ChartResult::default();
+        }
+        let content = chart_type.content().unwrap_or(options.content);
+        let mut records = queries::get_records_of_content(&self.records, content);
+        if chart_type.needs_extended_fields() {
+            records = queries::get_records_with_extended_fields(&records);
+        }
+        let records = &records;
+        let dt = &queries::get_records_in_period(records, period, options.timezone);
+        match compare_with {
+            Some(previous) => {
+                let previous = &queries::get_records_in_period(records, previous, options.timezone);
+                ChartResult {
+                    data: chart_type.query(records, dt, options),
+                    previous_data: chart_type.query(records, previous, options),
+                    comparison: chart_type.compare(records, dt, previous, options),
+                    summary: chart_type.summarize(dt, options),
+                }
+            }
+            None => ChartResult {
+                data: chart_type.query(records, dt, options),
+                previous_data: vec![],
+                comparison: None,
+                summary: chart_type.summarize(dt, options),
+            },
+        }
+    }
+}
+
+impl Worker for DataWorker {
+    type Message = DataWorkerMessage;
+    type Input = WorkerInput;
+    type Output = WorkerOutput;
+
+    fn create(scope: &WorkerScope<Self>) -> Self {
+        let scope = scope.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let records = storage::load_records().await.unwrap_or_default();
+            scope.send_message(DataWorkerMessage::Restored(records));
+        });
+        Self {
+            records: vec![],
+            revision: 0,
+            pending: Some(vec![]),
+            subscribers: HashMap::new(),
+            import: None,
+        }
+    }
+
+    fn update(&mut self, scope: &WorkerScope<Self>, msg: Self::Message) {
+        match msg {
+            DataWorkerMessage::Restored(records) => {
+                self.records = records;
+                self.changed(scope);
+                for (msg, id) in self.pending.take().unwrap_or_default() {
+                    self.received(scope, msg, id);
+                }
+            }
+            DataWorkerMessage::ContinueImport => self.continue_import(scope),
+        }
+    }
+
+    fn disconnected(&mut self, _scope: &WorkerScope<Self>, id: HandlerId) {
+        self.subscribers.remove(&id);
+        // Nobody is left to receive the report.
+        if self
+            .import
+            .as_ref()
+            .is_some_and(|import| import.handler == id)
+        {
+            self.import = None;
+        }
+    }
+
+    fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+        if let Some(pending) = &mut self.pending {
+            pending.push((msg, id));
+            return;
+        }
+        match msg {
+            WorkerInput::Import { id: import, files } => {
+                self.start_import(scope, id, import, files)
+            }
+            WorkerInput::CancelImport(import) => {
+                if self
+                    .import
+                    .as_ref()
+                    .is_some_and(|running| running.id == import)
+                {
+                    self.import = None;
+                }
+            }
+            WorkerInput::Update(action) => {
+                action.apply(&mut self.records);
+                self.changed(scope);
+            }
+            WorkerInput::Subscribe(timezone) => {
+                self.subscribers.insert(id, timezone);
+                self.summarize(scope, id, timezone);
+            }
+            WorkerInput::Query {
+                chart_type,
+                period,
+                compare_with,
+                options,
+            } => {
+                let result = self.query(chart_type, &period, &compare_with, &options);
+                scope.respond(id, WorkerOutput::Chart(result));
+            }
+            WorkerInput::Streaks {
+                period,
+                content,
+                artist,
+                timezone,
+            } => {
+                let records = &queries::get_records_of_content(&self.records, content);
+                let dt = &queries::get_records_in_period(records, &period, timezone);
+                let stats = get_streak_stats(records, dt, artist.as_deref(), timezone);
+                scope.respond(id, WorkerOutput::Streaks(stats));
+            }
+            WorkerInput::RankHistory {
+                period,
+                options,
+                interval,
+                window,
+            } => {
+                let records = &queries::get_records_of_content(&self.records, options.content);
+                let dt = &queries::get_records_in_period(records, &period, options.timezone);
+                let history = get_artist_rank_history(
+                    dt,
+                    interval,
+                    window,
+                    options.metric,
+                    options.timezone,
+                    RANKED_ARTISTS,
+                    MAX_SHOWN_RANK,
+                );
+                scope.respond(id, WorkerOutput::RankHistory(history));
+            }
+            WorkerInput::Calendar {
+                period,
+                content,
+                timezone,
+            } => {
+                let records = &queries::get_records_of_content(&self.records, content);
+                let dt = &queries::get_records_in_period(records, &period, timezone);
+                let days = get_calendar(dt, &period, timezone);
+                scope.respond(id, WorkerOutput::Calendar(days));
+            }
+            WorkerInput::DayPlays {
+                date,
+                content,
+                timezone,
+            } => {
+                let records = &queries::get_records_of_content(&self.records, content);
+                let plays = get_day_plays(records, date, timezone);
+                scope.respond(id, WorkerOutput::DayPlays(plays));
+            }
+        }
+    }
+}